@@ -0,0 +1,6 @@
+//! `no_std` build of the navigation math in `fc-backend`
+
+#![no_std]
+
+#[path = "../../src/planes/math.rs"]
+pub mod math;
@@ -0,0 +1,12 @@
+pub mod planes;
+#[cfg(feature = "tokio")]
+pub mod source;
+
+/// Commonly used types and functions: `use fc_backend::prelude::*;`
+pub mod prelude {
+    pub use crate::planes::{
+        Airplane, AirplaneError, Alert, AlertConfig, AlertSeverity, calculate_airplane_positions,
+        check_alert_between_planes, check_all_alerts, check_all_alerts_with,
+        generate_demo_airplanes, haversine_distance_nm, initial_bearing_deg,
+    };
+}
@@ -16,11 +16,9 @@ use tokio::{net::TcpListener, time::interval};
 use tokio_stream::StreamExt as _;
 use tower_http::cors::{Any, CorsLayer};
 
-use crate::planes::{calculate_airplane_positions, check_all_alerts, generate_demo_airplanes, Airplane, Alert};
+use fc_backend::planes::{calculate_airplane_positions, check_all_alerts, generate_demo_airplanes, Airplane, Alert};
 
-mod planes;
-
-static PLANES: Lazy<Vec<Airplane>> = Lazy::new(|| generate_demo_airplanes());
+static PLANES: Lazy<Vec<Airplane>> = Lazy::new(generate_demo_airplanes);
 static COUNTER: AtomicU64 = AtomicU64::new(0);
 
 #[derive(Serialize)]
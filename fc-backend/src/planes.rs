@@ -1,41 +1,4519 @@
-use serde::Serialize;
-use rand::Rng;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+pub mod math;
+
+pub use math::{
+    final_bearing_deg, great_circle_midpoint, haversine_distance_nm, initial_bearing_deg,
+    to_enu_meters,
+};
+use math::{
+    cos_deg, great_circle_intermediate, haversine_distance_with_cos_nm, offset_position,
+    offset_position_along, offset_position_along_with,
+};
 
 /// Number of demo airplanes to generate (configurable constant)
 const NUM_DEMO_PLANES: usize = 20;
 
+/// Latitude of Linz Airport (LNZ)
+pub const LNZ_LAT: f64 = 48.238575;
+/// Longitude of Linz Airport (LNZ)
+pub const LNZ_LNG: f64 = 14.191473;
+
+/// Radius of the simulated airspace around LNZ in kilometers
+const AIRSPACE_RADIUS_KM: f64 = 100.0;
+
+/// Horizontal separation below which an alert is raised (nautical miles, exclusive)
+pub const ALERT_DISTANCE_NM: f64 = 5.0;
+/// Vertical separation below which an alert is raised (feet, exclusive)
+pub const ALERT_ALTITUDE_DIFF_FT: f64 = 1000.0;
+
+const KM_PER_NM: f64 = 1.852;
+
+/// ISA pressure at mean sea level
+const ISA_SEA_LEVEL_PRESSURE_HPA: f64 = 1013.25;
+/// Constants of the ISA barometric formula h = 145366.45 * (1 - (p / p0)^0.190263)
+const BAROMETRIC_SCALE_FT: f64 = 145366.45;
+const BAROMETRIC_EXPONENT: f64 = 0.190263;
+
+/// Feet per second in one knot
+const FT_PER_S_PER_KNOT: f64 = 1.68781;
+/// Standard gravity in feet per second squared
+const GRAVITY_FT_PER_S2: f64 = 32.174;
+
+/// Aircraft types used for demo data generation (ICAO type designators)
+const AIRCRAFT_TYPES: &[&str] = &[
+    "A320", "A321", "A359", "B738", "B789", "B77W", "E190", "CRJ9", "DH8D", "AT76",
+];
+
 #[derive(Debug, Clone, Serialize)]
 pub struct Airplane {
-    // TODO: Add fields for airplane data
+    /// Numeric identifier that stays stable when the callsign changes (0 if unassigned)
+    pub id: u64,
+    /// Flight number, e.g. "AAL123"
+    pub callsign: String,
+    /// ICAO aircraft type designator, e.g. "A320"
+    pub aircraft_type: String,
+    /// Latitude in degrees
+    pub latitude: f64,
+    /// Longitude in degrees
+    pub longitude: f64,
+    /// Altitude in feet
+    pub altitude: f64,
+    /// Ground speed in knots
+    pub speed: f64,
+    /// Heading in degrees from magnetic north
+    pub heading: f64,
+    /// Reference of `altitude`
+    pub altitude_type: AltitudeType,
+    /// Level cleared by ATC in feet, if any
+    pub assigned_altitude_ft: Option<f64>,
+    /// Scenario time of the last position report in seconds
+    pub last_update_s: f64,
+    /// Free-form labels for grouping in the UI, e.g. a color or category
+    pub tags: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// How an altitude value is referenced
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum AltitudeType {
+    /// Altitude relative to the standard pressure of 1013.25 hPa (as reported by ADS-B)
+    Pressure,
+    /// Geometric altitude above mean sea level
+    Geometric,
+}
+
+/// Tolerances for [`Airplane::approx_eq_with`], one per unit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ApproxEps {
+    /// Latitude and longitude in degrees
+    pub coord: f64,
+    /// Altitude in feet
+    pub alt: f64,
+    /// Speed in knots
+    pub speed: f64,
+    /// Heading in degrees (shortest arc)
+    pub heading: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Alert {
-    // TODO: Add fields for alert data
+    pub plane1_callsign: String,
+    pub plane2_callsign: String,
+    /// Horizontal distance between the planes in nautical miles
+    pub distance_nm: f64,
+    /// Absolute altitude difference in feet
+    pub altitude_diff_ft: f64,
+    pub severity: AlertSeverity,
+    /// How the two tracks meet
+    pub geometry: ConflictGeometry,
+    /// Whether the loss of separation is predicted within the look-ahead window rather than
+    /// current; distance and altitude difference are then those at the predicted breach
+    pub predicted: bool,
+}
+
+/// Urgency of an alert, derived from the horizontal distance inside the alert box
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum AlertSeverity {
+    /// Closer than 5nm
+    Advisory,
+    /// Closer than 2.5nm
+    Warning,
+    /// Closer than 1nm
+    Critical,
+}
+
+impl AlertSeverity {
+    pub const ALL: [AlertSeverity; 3] =
+        [AlertSeverity::Advisory, AlertSeverity::Warning, AlertSeverity::Critical];
+
+    /// Severity for a pair at the given horizontal distance
+    pub fn for_distance(distance_nm: f64) -> Self {
+        if distance_nm < 1.0 {
+            AlertSeverity::Critical
+        } else if distance_nm < 2.5 {
+            AlertSeverity::Warning
+        } else {
+            AlertSeverity::Advisory
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertSeverity::Advisory => "Advisory",
+            AlertSeverity::Warning => "Warning",
+            AlertSeverity::Critical => "Critical",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        AlertSeverity::ALL.into_iter().find(|severity| severity.as_str() == value)
+    }
+}
+
+/// Encounter geometry of a pair, classified by the angle between their headings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum ConflictGeometry {
+    /// Headings differ by more than 135°
+    HeadOn,
+    /// Headings differ by 45° to 135°
+    Crossing,
+    /// Headings differ by less than 45°
+    Overtaking,
+}
+
+impl ConflictGeometry {
+    pub const ALL: [ConflictGeometry; 3] =
+        [ConflictGeometry::HeadOn, ConflictGeometry::Crossing, ConflictGeometry::Overtaking];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConflictGeometry::HeadOn => "HeadOn",
+            ConflictGeometry::Crossing => "Crossing",
+            ConflictGeometry::Overtaking => "Overtaking",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        ConflictGeometry::ALL.into_iter().find(|geometry| geometry.as_str() == value)
+    }
+}
+
+impl Alert {
+    fn between(plane1: &Airplane, plane2: &Airplane, distance_nm: f64, altitude_diff_ft: f64) -> Self {
+        Alert {
+            plane1_callsign: plane1.callsign.clone(),
+            plane2_callsign: plane2.callsign.clone(),
+            distance_nm,
+            altitude_diff_ft,
+            severity: AlertSeverity::for_distance(distance_nm),
+            geometry: conflict_geometry(plane1, plane2),
+            predicted: false,
+        }
+    }
+}
+
+/// ICAO wake turbulence category
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum WakeCategory {
+    Light,
+    Medium,
+    Heavy,
+    Super,
+}
+
+/// Reasons why airplane data is considered invalid
+#[derive(Debug, Clone, PartialEq)]
+pub enum AirplaneError {
+    EmptyCallsign,
+    InvalidLatitude(f64),
+    InvalidLongitude(f64),
+    InvalidAltitude(f64),
+    InvalidSpeed(f64),
+    InvalidHeading(f64),
+}
+
+impl fmt::Display for AirplaneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AirplaneError::EmptyCallsign => write!(f, "callsign must not be empty"),
+            AirplaneError::InvalidLatitude(v) => write!(f, "latitude {v} is outside [-90, 90]"),
+            AirplaneError::InvalidLongitude(v) => write!(f, "longitude {v} is outside [-180, 180]"),
+            AirplaneError::InvalidAltitude(v) => write!(f, "altitude {v} must be a non-negative number"),
+            AirplaneError::InvalidSpeed(v) => write!(f, "speed {v} must be a non-negative number"),
+            AirplaneError::InvalidHeading(v) => write!(f, "heading {v} is outside [0, 360)"),
+        }
+    }
+}
+
+impl std::error::Error for AirplaneError {}
+
+/// Error while parsing CSV data, with the 1-based line number
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+impl Airplane {
+    pub fn new(
+        callsign: &str,
+        aircraft_type: &str,
+        latitude: f64,
+        longitude: f64,
+        altitude: f64,
+        speed: f64,
+        heading: f64,
+    ) -> Self {
+        Airplane {
+            id: 0,
+            callsign: callsign.to_string(),
+            aircraft_type: aircraft_type.to_string(),
+            latitude,
+            longitude,
+            altitude,
+            speed,
+            heading,
+            altitude_type: AltitudeType::Pressure,
+            assigned_altitude_ft: None,
+            last_update_s: 0.0,
+            tags: Vec::new(),
+        }
+    }
+
+    /// Create a plane and [`validate`](Airplane::validate) it
+    pub fn new_checked(
+        callsign: &str,
+        aircraft_type: &str,
+        latitude: f64,
+        longitude: f64,
+        altitude: f64,
+        speed: f64,
+        heading: f64,
+    ) -> Result<Self, AirplaneError> {
+        let plane = Airplane::new(callsign, aircraft_type, latitude, longitude, altitude, speed, heading);
+        plane.validate()?;
+        Ok(plane)
+    }
+
+    /// Check that all fields are within their physically meaningful ranges
+    pub fn validate(&self) -> Result<(), AirplaneError> {
+        if self.callsign.trim().is_empty() {
+            return Err(AirplaneError::EmptyCallsign);
+        }
+        if !(-90.0..=90.0).contains(&self.latitude) {
+            return Err(AirplaneError::InvalidLatitude(self.latitude));
+        }
+        if !(-180.0..=180.0).contains(&self.longitude) {
+            return Err(AirplaneError::InvalidLongitude(self.longitude));
+        }
+        if !(self.altitude.is_finite() && self.altitude >= 0.0) {
+            return Err(AirplaneError::InvalidAltitude(self.altitude));
+        }
+        if !(self.speed.is_finite() && self.speed >= 0.0) {
+            return Err(AirplaneError::InvalidSpeed(self.speed));
+        }
+        if !(0.0..360.0).contains(&self.heading) {
+            return Err(AirplaneError::InvalidHeading(self.heading));
+        }
+        Ok(())
+    }
+
+    /// Altitude above mean sea level for the given QNH in hPa
+    ///
+    /// Pressure altitudes are converted with the ISA barometric formula, geometric altitudes
+    /// are returned unchanged.
+    pub fn geometric_altitude_ft(&self, qnh_hpa: f64) -> f64 {
+        match self.altitude_type {
+            AltitudeType::Geometric => self.altitude,
+            AltitudeType::Pressure => {
+                let pressure_hpa = ISA_SEA_LEVEL_PRESSURE_HPA
+                    * (1.0 - self.altitude / BAROMETRIC_SCALE_FT).powf(1.0 / BAROMETRIC_EXPONENT);
+                BAROMETRIC_SCALE_FT * (1.0 - (pressure_hpa / qnh_hpa).powf(BAROMETRIC_EXPONENT))
+            }
+        }
+    }
+
+    /// Initial great-circle bearing from the plane to the given point in degrees
+    pub fn bearing_to(&self, lat: f64, lng: f64) -> f64 {
+        initial_bearing_deg(self.latitude, self.longitude, lat, lng)
+    }
+
+    /// Compare two planes with tolerances for the numeric fields
+    ///
+    /// Callsign and aircraft type must match exactly. Latitude, longitude and heading (shortest
+    /// arc, also in degrees) must be within `coord_eps`, altitude within `alt_eps`. Speed must
+    /// be within the difference that drifts the planes `coord_eps` apart per hour. Use
+    /// [`approx_eq_with`](Airplane::approx_eq_with) to set each tolerance separately.
+    pub fn approx_eq(&self, other: &Airplane, coord_eps: f64, alt_eps: f64) -> bool {
+        let eps = ApproxEps {
+            coord: coord_eps,
+            alt: alt_eps,
+            speed: coord_eps * math::NM_PER_DEGREE,
+            heading: coord_eps,
+        };
+        self.approx_eq_with(other, &eps)
+    }
+
+    /// Compare two planes with a separate tolerance for every unit
+    ///
+    /// Callsign and aircraft type must match exactly. Every numeric field must be within the
+    /// tolerance for its unit in `eps`.
+    pub fn approx_eq_with(&self, other: &Airplane, eps: &ApproxEps) -> bool {
+        self.callsign == other.callsign
+            && self.aircraft_type == other.aircraft_type
+            && (self.latitude - other.latitude).abs() <= eps.coord
+            && (self.longitude - other.longitude).abs() <= eps.coord
+            && (self.altitude - other.altitude).abs() <= eps.alt
+            && (self.speed - other.speed).abs() <= eps.speed
+            && heading_difference_deg(self.heading, other.heading) <= eps.heading
+    }
+
+    /// Whether the sun is above the horizon at the plane's position at the given time
+    ///
+    /// Uses a low-precision solar position approximation (accurate to a few minutes around
+    /// sunrise and sunset) and the standard -0.833° sunrise/sunset elevation.
+    pub fn is_daytime(&self, unix_time_s: i64) -> bool {
+        solar_elevation_deg(self.latitude, self.longitude, unix_time_s) > -0.833
+    }
+
+    /// Seconds since the last position report at scenario time `now_s`
+    pub fn age_s(&self, now_s: f64) -> f64 {
+        now_s - self.last_update_s
+    }
+
+    /// Magnitude of the velocity relative to `other` in knots (never negative)
+    ///
+    /// Unlike the range rate this is independent of the relative position, e.g. 240kn for two
+    /// planes flying head-on at 120kn each.
+    pub fn closing_speed_kn(&self, other: &Airplane) -> f64 {
+        let (north, east) = relative_velocity_kn(self, other);
+        north.hypot(east)
+    }
+
+    /// Specific energy height in feet: altitude plus the height equivalent of the speed (v²/2g)
+    ///
+    /// The ground speed is used as an approximation of the true airspeed.
+    pub fn specific_energy_ft(&self) -> f64 {
+        let speed_ft_per_s = self.speed * FT_PER_S_PER_KNOT;
+        self.altitude + speed_ft_per_s * speed_ft_per_s / (2.0 * GRAVITY_FT_PER_S2)
+    }
+}
+
+/// Elevation of the sun above the horizon in degrees
+fn solar_elevation_deg(lat: f64, lng: f64, unix_time_s: i64) -> f64 {
+    // Days since the J2000.0 epoch
+    let n = unix_time_s as f64 / 86400.0 + 2440587.5 - 2451545.0;
+
+    // Ecliptic coordinates of the sun
+    let mean_longitude = (280.460 + 0.9856474 * n).rem_euclid(360.0);
+    let mean_anomaly = (357.528 + 0.9856003 * n).rem_euclid(360.0).to_radians();
+    let ecliptic_longitude = (mean_longitude
+        + 1.915 * mean_anomaly.sin()
+        + 0.020 * (2.0 * mean_anomaly).sin())
+    .to_radians();
+    let obliquity = (23.439 - 0.0000004 * n).to_radians();
+
+    // Equatorial coordinates
+    let right_ascension = (obliquity.cos() * ecliptic_longitude.sin()).atan2(ecliptic_longitude.cos());
+    let declination = (obliquity.sin() * ecliptic_longitude.sin()).asin();
+
+    // Local hour angle from Greenwich mean sidereal time
+    let gmst_hours = (18.697374558 + 24.06570982441908 * n).rem_euclid(24.0);
+    let hour_angle = (gmst_hours * 15.0 + lng).to_radians() - right_ascension;
+
+    let lat = lat.to_radians();
+    (lat.sin() * declination.sin() + lat.cos() * declination.cos() * hour_angle.cos())
+        .asin()
+        .to_degrees()
+}
+
+/// Validate all planes and collect every error together with the index of the offending plane
+pub fn validate_all(planes: &[Airplane]) -> Vec<(usize, AirplaneError)> {
+    planes
+        .iter()
+        .enumerate()
+        .filter_map(|(index, plane)| plane.validate().err().map(|err| (index, err)))
+        .collect()
+}
+
+/// Absolute altitude difference between two planes in feet
+pub fn altitude_diff_ft(plane1: &Airplane, plane2: &Airplane) -> f64 {
+    (plane1.altitude - plane2.altitude).abs()
+}
+
+/// Generate a random, unique callsign (three letters followed by three digits)
+fn generate_callsign(rng: &mut impl Rng, used: &mut HashSet<String>) -> String {
+    loop {
+        let letters: String = (0..3).map(|_| rng.random_range(b'A'..=b'Z') as char).collect();
+        let callsign = format!("{letters}{:03}", rng.random_range(0..1000));
+        if used.insert(callsign.clone()) {
+            return callsign;
+        }
+    }
+}
+
+/// Settings for random traffic generation
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratorConfig {
+    /// Exponent applied to the sampled distance from the airport
+    ///
+    /// `1.0` spreads planes uniformly over the distance range, larger values concentrate
+    /// them near the airport (distance = radius * u^density_bias).
+    pub density_bias: f64,
+    /// Airport (lat, lng) around which planes are scattered
+    pub center: (f64, f64),
+    /// Lowest altitude for generated planes (feet, inclusive)
+    pub min_alt: f64,
+    /// Highest altitude for generated planes (feet, exclusive); must be above `min_alt`
+    pub max_alt: f64,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        GeneratorConfig {
+            density_bias: 1.0,
+            center: (LNZ_LAT, LNZ_LNG),
+            min_alt: 15000.0,
+            max_alt: 35000.0,
+        }
+    }
+}
+
+/// Error returned for an inconsistent generator configuration
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeneratorConfigError {
+    InvalidAltitudeRange { min_alt: f64, max_alt: f64 },
+    /// No generated aircraft type can reach `min_alt`
+    AboveServiceCeiling { min_alt: f64, ceiling_ft: f64 },
+}
+
+impl fmt::Display for GeneratorConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeneratorConfigError::InvalidAltitudeRange { min_alt, max_alt } => {
+                write!(f, "invalid altitude range: {min_alt} ft must be below {max_alt} ft")
+            }
+            GeneratorConfigError::AboveServiceCeiling { min_alt, ceiling_ft } => {
+                write!(f, "minimum altitude {min_alt} ft exceeds every ceiling ({ceiling_ft} ft)")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GeneratorConfigError {}
+
+impl GeneratorConfig {
+    /// Generate planes between `min_alt` and `max_alt` feet instead of the default cruise band
+    pub fn with_altitude_range(
+        self,
+        min_alt: f64,
+        max_alt: f64,
+    ) -> Result<Self, GeneratorConfigError> {
+        GeneratorConfig { min_alt, max_alt, ..self }.validate()
+    }
+
+    /// Check that the configuration can be used for generation
+    pub fn validate(self) -> Result<Self, GeneratorConfigError> {
+        let finite = self.min_alt.is_finite() && self.max_alt.is_finite();
+        if !finite || self.min_alt >= self.max_alt {
+            return Err(GeneratorConfigError::InvalidAltitudeRange {
+                min_alt: self.min_alt,
+                max_alt: self.max_alt,
+            });
+        }
+        let ceiling_ft =
+            AIRCRAFT_TYPES.iter().map(|t| service_ceiling_ft(t)).fold(f64::MIN, f64::max);
+        if self.min_alt >= ceiling_ft {
+            return Err(GeneratorConfigError::AboveServiceCeiling {
+                min_alt: self.min_alt,
+                ceiling_ft,
+            });
+        }
+        Ok(self)
+    }
+}
+
+/// Distance from the airport for a new plane, biased towards the airport by the config
+fn sample_distance_km(rng: &mut impl Rng, config: &GeneratorConfig) -> f64 {
+    AIRSPACE_RADIUS_KM * rng.random::<f64>().powf(config.density_bias)
+}
+
+/// Generate a random plane with the given `id` within the airspace around the configured center
+fn random_airplane(
+    rng: &mut impl Rng,
+    used: &mut HashSet<String>,
+    id: u64,
+    config: &GeneratorConfig,
+) -> Airplane {
+    // Random position within the airspace around the center
+    let distance_km = sample_distance_km(rng, config);
+    let bearing = rng.random_range(0.0..360.0_f64);
+    let (center_lat, center_lng) = config.center;
+    let (latitude, longitude) =
+        offset_position_along(center_lat, center_lng, bearing, distance_km / KM_PER_NM);
+
+    let callsign = generate_callsign(rng, used);
+    // Only types that can climb into the configured band, each kept below its ceiling
+    let types: Vec<&str> = AIRCRAFT_TYPES
+        .iter()
+        .copied()
+        .filter(|aircraft_type| service_ceiling_ft(aircraft_type) > config.min_alt)
+        .collect();
+    let aircraft_type = types[rng.random_range(0..types.len())];
+    let max_alt = config.max_alt.min(service_ceiling_ft(aircraft_type));
+    let mut plane = Airplane::new(
+        &callsign,
+        aircraft_type,
+        latitude,
+        longitude,
+        rng.random_range(config.min_alt..max_alt).round(),
+        rng.random_range(250.0..500.0_f64).round(),
+        rng.random_range(0.0..360.0_f64).round() % 360.0,
+    );
+    plane.id = id;
+    plane
+}
+
+/// Fill `planes` up to `count` entries with random planes, keeping callsigns unique
+fn fill_with_random(
+    rng: &mut impl Rng,
+    mut planes: Vec<Airplane>,
+    count: usize,
+    config: &GeneratorConfig,
+) -> Vec<Airplane> {
+    let mut used: HashSet<String> = planes.iter().map(|p| p.callsign.clone()).collect();
+    while planes.len() < count {
+        let id = planes.len() as u64 + 1;
+        planes.push(random_airplane(rng, &mut used, id, config));
+    }
+    planes
+}
+
+/// Generate a fleet of `count` planes starting with the two fixed test planes
+fn generate_fleet(rng: &mut impl Rng, count: usize, config: &GeneratorConfig) -> Vec<Airplane> {
+    // Fixed planes on a collision course, used for alert detection
+    let mut planes = vec![
+        Airplane::new("TEST001", "A320", 48.288158, 14.191473, 30000.0, 120.0, 180.0),
+        Airplane::new("TEST002", "B738", 48.188992, 14.191473, 29500.0, 120.0, 0.0),
+    ];
+    for (index, plane) in planes.iter_mut().enumerate() {
+        plane.id = index as u64 + 1;
+    }
+    planes.truncate(count);
+    fill_with_random(rng, planes, count, config)
 }
 
 /// Generate demo airplane data
 pub fn generate_demo_airplanes() -> Vec<Airplane> {
-    todo!()
+    generate_demo_airplanes_count(NUM_DEMO_PLANES)
+}
+
+/// Generate `count` demo airplanes (including the two test planes)
+///
+/// Callsigns are unique within one call only; use [`generate_unique_fleet`] for larger fleets.
+pub fn generate_demo_airplanes_count(count: usize) -> Vec<Airplane> {
+    generate_fleet(&mut rand::rng(), count, &GeneratorConfig::default())
+}
+
+/// Generate a reproducible fleet of `total` planes with callsigns unique across the whole fleet
+pub fn generate_unique_fleet(total: usize, seed: u64) -> Vec<Airplane> {
+    generate_fleet(&mut StdRng::seed_from_u64(seed), total, &GeneratorConfig::default())
+}
+
+/// Generate `n` purely random planes, without the fixed TEST001/TEST002 planes
+pub fn generate_random_airplanes(n: usize, seed: u64) -> Vec<Airplane> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    fill_with_random(&mut rng, Vec::with_capacity(n), n, &GeneratorConfig::default())
+}
+
+/// Generate `count` reproducible random planes with custom settings
+///
+/// The fixed TEST001/TEST002 planes are not included, so every plane follows the config.
+/// Fails if the config does not pass [`GeneratorConfig::validate`].
+pub fn generate_airplanes_with(
+    count: usize,
+    config: &GeneratorConfig,
+    seed: u64,
+) -> Result<Vec<Airplane>, GeneratorConfigError> {
+    let config = config.clone().validate()?;
+    let mut rng = StdRng::seed_from_u64(seed);
+    Ok(fill_with_random(&mut rng, Vec::with_capacity(count), count, &config))
+}
+
+/// Flat-earth model used to move planes along their headings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositionModel {
+    /// Nautical miles per degree of latitude (60 on earth)
+    pub nm_per_degree: f64,
+}
+
+impl Default for PositionModel {
+    fn default() -> Self {
+        PositionModel { nm_per_degree: math::NM_PER_DEGREE }
+    }
+}
+
+impl PositionModel {
+    /// Move a single plane along its heading for the given time
+    ///
+    /// The moved plane's `last_update_s` is advanced by the same time.
+    pub fn advance(&self, plane: &Airplane, elapsed_seconds: f64) -> Airplane {
+        let distance_nm = plane.speed * elapsed_seconds / 3600.0;
+        let mut updated = plane.clone();
+        (updated.latitude, updated.longitude) = offset_position_along_with(
+            plane.latitude,
+            plane.longitude,
+            plane.heading,
+            distance_nm,
+            self.nm_per_degree,
+        );
+        updated.altitude = updated.altitude.min(service_ceiling_ft(&plane.aircraft_type));
+        reflect_over_pole(&mut updated);
+        updated.last_update_s = plane.last_update_s + elapsed_seconds;
+        updated
+    }
+
+    /// Calculate updated airplane positions based on elapsed time
+    pub fn positions(&self, planes: &[Airplane], elapsed_seconds: f64) -> Vec<Airplane> {
+        planes.iter().map(|plane| self.advance(plane, elapsed_seconds)).collect()
+    }
+}
+
+/// Bring a latitude beyond ±90° back into range by crossing over the pole
+///
+/// The latitude is folded periodically, so a single step may cross the poles any number of
+/// times. On every crossing the plane continues on the opposite meridian, so its longitude
+/// shifts by 180° and its heading is reversed (e.g. northbound becomes southbound). Longitudes
+/// leaving [-180, 180] are wrapped to [-180, 180); positions in range are left untouched.
+fn reflect_over_pole(plane: &mut Airplane) {
+    if plane.latitude.abs() > 90.0 {
+        let folded = (plane.latitude + 90.0).rem_euclid(360.0);
+        let crossed_odd = folded > 180.0;
+        plane.latitude = if crossed_odd { 270.0 - folded } else { folded - 90.0 };
+        if crossed_odd {
+            plane.longitude += 180.0;
+            plane.heading = (plane.heading + 180.0).rem_euclid(360.0);
+        }
+    }
+    if plane.longitude.abs() > 180.0 {
+        plane.longitude = (plane.longitude + 180.0).rem_euclid(360.0) - 180.0;
+    }
 }
 
 /// Calculate updated airplane positions based on elapsed time
+///
+/// Uses the default [`PositionModel`]; call [`PositionModel::positions`] for a custom one.
 pub fn calculate_airplane_positions(planes: &[Airplane], elapsed_seconds: f64) -> Vec<Airplane> {
-    todo!()
+    PositionModel::default().positions(planes, elapsed_seconds)
+}
+
+/// Calculate updated airplane positions and let `hook` adjust each plane afterwards
+///
+/// The hook receives the moved plane and the elapsed time, e.g. to change heading or speed
+/// for scripted maneuvers.
+pub fn calculate_airplane_positions_with(
+    planes: &[Airplane],
+    elapsed_s: f64,
+    hook: impl Fn(&mut Airplane, f64),
+) -> Vec<Airplane> {
+    planes
+        .iter()
+        .map(|plane| {
+            let mut updated = PositionModel::default().advance(plane, elapsed_s);
+            hook(&mut updated, elapsed_s);
+            updated
+        })
+        .collect()
+}
+
+/// Predict the future track of a plane as `steps` lat/lng positions spaced `step_s` apart
+///
+/// The current position is not included.
+pub fn predict_trajectory(plane: &Airplane, step_s: f64, steps: usize) -> Vec<(f64, f64)> {
+    let mut current = plane.clone();
+    (0..steps)
+        .map(|_| {
+            current = PositionModel::default().advance(&current, step_s);
+            (current.latitude, current.longitude)
+        })
+        .collect()
+}
+
+/// Horizontal distance between two planes in nautical miles (Haversine formula)
+pub fn horizontal_distance_nm(plane1: &Airplane, plane2: &Airplane) -> f64 {
+    haversine_distance_nm(plane1.latitude, plane1.longitude, plane2.latitude, plane2.longitude)
+}
+
+/// Settings for alert detection
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertConfig {
+    /// Horizontal separation below which an alert is raised (nautical miles)
+    pub distance_nm: f64,
+    /// Vertical separation below which an alert is raised (feet)
+    pub altitude_diff_ft: f64,
+    /// If set, only pairs where both planes have one of these aircraft types are checked
+    pub aircraft_types: Option<HashSet<String>>,
+    /// Whether pairs exactly at a threshold alert as well (default: thresholds are exclusive)
+    pub edge_inclusive: bool,
+    /// If set, also raise predicted alerts for pairs breaching within this many seconds
+    ///
+    /// Windows that are not positive and finite are ignored.
+    pub look_ahead_s: Option<f64>,
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        AlertConfig {
+            distance_nm: ALERT_DISTANCE_NM,
+            altitude_diff_ft: ALERT_ALTITUDE_DIFF_FT,
+            aircraft_types: None,
+            edge_inclusive: false,
+            look_ahead_s: None,
+        }
+    }
+}
+
+impl AlertConfig {
+    /// Whether the given separation breaches both thresholds
+    ///
+    /// Both thresholds are exclusive unless [`edge_inclusive`](AlertConfig::edge_inclusive) is set.
+    pub fn is_loss_of_separation(&self, distance_nm: f64, altitude_diff_ft: f64) -> bool {
+        if self.edge_inclusive {
+            distance_nm <= self.distance_nm && altitude_diff_ft <= self.altitude_diff_ft
+        } else {
+            distance_nm < self.distance_nm && altitude_diff_ft < self.altitude_diff_ft
+        }
+    }
+
+    /// Whether the pair is subject to alerting according to the aircraft type filter
+    fn applies_to(&self, plane1: &Airplane, plane2: &Airplane) -> bool {
+        match &self.aircraft_types {
+            Some(types) => {
+                types.contains(&plane1.aircraft_type) && types.contains(&plane2.aircraft_type)
+            }
+            None => true,
+        }
+    }
+}
+
+/// Whether the given separation breaches both default alert thresholds
+///
+/// Both thresholds are exclusive: a pair exactly [`ALERT_DISTANCE_NM`] or exactly
+/// [`ALERT_ALTITUDE_DIFF_FT`] apart is still considered separated.
+pub fn is_loss_of_separation(distance_nm: f64, altitude_diff_ft: f64) -> bool {
+    AlertConfig::default().is_loss_of_separation(distance_nm, altitude_diff_ft)
+}
+
+/// Build an alert for two planes if the given separation breaches the configured thresholds
+fn alert_for_separation(
+    plane1: &Airplane,
+    plane2: &Airplane,
+    distance_nm: f64,
+    config: &AlertConfig,
+) -> Option<Alert> {
+    let altitude_diff_ft = altitude_diff_ft(plane1, plane2);
+
+    if config.is_loss_of_separation(distance_nm, altitude_diff_ft) {
+        Some(Alert::between(plane1, plane2, distance_nm, altitude_diff_ft))
+    } else {
+        None
+    }
 }
 
 /// Check if two airplanes trigger an alert using Haversine formula
+///
+/// An alert requires both a horizontal and a vertical loss of separation, so planes that are
+/// horizontally close but vertically separated never alert.
 pub fn check_alert_between_planes(plane1: &Airplane, plane2: &Airplane) -> Option<Alert> {
-    todo!()
+    alert_for_separation(
+        plane1,
+        plane2,
+        horizontal_distance_nm(plane1, plane2),
+        &AlertConfig::default(),
+    )
 }
 
 /// Check all combinations of airplanes for alerts
 pub fn check_all_alerts(planes: &[Airplane]) -> Vec<Alert> {
-    todo!()
+    check_all_alerts_with(planes, &AlertConfig::default(), horizontal_distance_nm)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Check all combinations of airplanes for alerts using custom settings and distance metric
+///
+/// `distance_fn` returns the separation of two planes in nautical miles, which is compared
+/// against [`AlertConfig::distance_nm`]. [`check_all_alerts`] uses [`horizontal_distance_nm`].
+pub fn check_all_alerts_with(
+    planes: &[Airplane],
+    config: &AlertConfig,
+    distance_fn: impl Fn(&Airplane, &Airplane) -> f64,
+) -> Vec<Alert> {
+    check_all_alerts_indexed_with(planes, config, distance_fn)
+        .into_iter()
+        .map(|(_, _, alert)| alert)
+        .collect()
+}
+
+/// Check all combinations of airplanes for alerts, returning the slice indices of each pair
+pub fn check_all_alerts_indexed(planes: &[Airplane]) -> Vec<(usize, usize, Alert)> {
+    check_all_alerts_indexed_with(planes, &AlertConfig::default(), horizontal_distance_nm)
+}
+
+fn check_all_alerts_indexed_with(
+    planes: &[Airplane],
+    config: &AlertConfig,
+    distance_fn: impl Fn(&Airplane, &Airplane) -> f64,
+) -> Vec<(usize, usize, Alert)> {
+    let mut alerts = Vec::new();
+    for (i, plane1) in planes.iter().enumerate() {
+        for (j, plane2) in planes.iter().enumerate().skip(i + 1) {
+            if !config.applies_to(plane1, plane2) {
+                continue;
+            }
+            let distance_nm = distance_fn(plane1, plane2);
+            let alert = alert_for_separation(plane1, plane2, distance_nm, config)
+                .or_else(|| predicted_alert(plane1, plane2, config, &distance_fn));
+            if let Some(alert) = alert {
+                alerts.push((i, j, alert));
+            }
+        }
+    }
+    alerts
+}
+
+/// Time step used when searching the look-ahead window for a predicted conflict (seconds)
+const LOOK_AHEAD_STEP_S: f64 = 10.0;
+
+/// First predicted loss of separation within the configured look-ahead window, if any
+fn predicted_alert(
+    plane1: &Airplane,
+    plane2: &Airplane,
+    config: &AlertConfig,
+    distance_fn: impl Fn(&Airplane, &Airplane) -> f64,
+) -> Option<Alert> {
+    let window_s = config.look_ahead_s.filter(|w| w.is_finite() && *w > 0.0)?;
+    let steps = (window_s / LOOK_AHEAD_STEP_S).ceil().max(0.0) as usize;
+    let model = PositionModel::default();
+    (1..=steps).find_map(|step| {
+        let elapsed = (step as f64 * LOOK_AHEAD_STEP_S).min(window_s);
+        let future1 = model.advance(plane1, elapsed);
+        let future2 = model.advance(plane2, elapsed);
+        let mut alert =
+            alert_for_separation(&future1, &future2, distance_fn(&future1, &future2), config)?;
+        alert.predicted = true;
+        Some(alert)
+    })
+}
+
+/// Compute the convex hull (lat/lng, counter-clockwise) of all plane positions
+///
+/// Uses the monotone-chain algorithm on positions projected with longitude scaled by the
+/// cosine of the mean latitude. Fewer than three distinct positions are returned as they are
+/// (without duplicates).
+pub fn traffic_convex_hull(planes: &[Airplane]) -> Vec<(f64, f64)> {
+    let mean_lat = planes.iter().map(|p| p.latitude).sum::<f64>() / planes.len() as f64;
+    let scale = mean_lat.to_radians().cos();
+
+    // Projected points as (x, y, lat, lng)
+    let mut points: Vec<(f64, f64, f64, f64)> = planes
+        .iter()
+        .map(|p| (p.longitude * scale, p.latitude, p.latitude, p.longitude))
+        .collect();
+    points.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    points.dedup_by(|a, b| a.0 == b.0 && a.1 == b.1);
+    if points.len() < 3 {
+        return points.into_iter().map(|(_, _, lat, lng)| (lat, lng)).collect();
+    }
+
+    let cross = |o: &(f64, f64, f64, f64), a: &(f64, f64, f64, f64), b: &(f64, f64, f64, f64)| {
+        (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+    };
+
+    let mut hull: Vec<(f64, f64, f64, f64)> = Vec::with_capacity(points.len() * 2);
+    // Lower hull
+    for point in &points {
+        while hull.len() >= 2 && cross(&hull[hull.len() - 2], &hull[hull.len() - 1], point) <= 0.0 {
+            hull.pop();
+        }
+        hull.push(*point);
+    }
+    // Upper hull
+    let lower_len = hull.len() + 1;
+    for point in points.iter().rev().skip(1) {
+        while hull.len() >= lower_len
+            && cross(&hull[hull.len() - 2], &hull[hull.len() - 1], point) <= 0.0
+        {
+            hull.pop();
+        }
+        hull.push(*point);
+    }
+    // The last point equals the first one
+    hull.pop();
+
+    hull.into_iter().map(|(_, _, lat, lng)| (lat, lng)).collect()
+}
+
+/// Draw a sample from the standard normal distribution (Box-Muller transform)
+fn sample_standard_normal(rng: &mut impl Rng) -> f64 {
+    // Shift into (0, 1] so that ln() stays finite
+    let u1 = 1.0 - rng.random::<f64>();
+    let u2 = rng.random::<f64>();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+/// Perturb positions and altitudes with Gaussian noise to simulate sensor inaccuracy
+///
+/// Horizontal noise is applied independently to the north and east components. The result
+/// is deterministic for a given seed.
+pub fn add_position_noise(
+    planes: &[Airplane],
+    std_dev_nm: f64,
+    alt_std_dev_ft: f64,
+    seed: u64,
+) -> Vec<Airplane> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    planes
+        .iter()
+        .map(|plane| {
+            let north_nm = sample_standard_normal(&mut rng) * std_dev_nm;
+            let east_nm = sample_standard_normal(&mut rng) * std_dev_nm;
+            let mut noisy = plane.clone();
+            (noisy.latitude, noisy.longitude) =
+                offset_position(plane.latitude, plane.longitude, north_nm, east_nm);
+            noisy.altitude += sample_standard_normal(&mut rng) * alt_std_dev_ft;
+            noisy
+        })
+        .collect()
+}
+
+/// Smallest angle between two headings in degrees (0..=180)
+fn heading_difference_deg(heading1: f64, heading2: f64) -> f64 {
+    let diff = (heading1 - heading2).rem_euclid(360.0);
+    if diff > 180.0 { 360.0 - diff } else { diff }
+}
+
+/// Find pairs of planes flying in opposite directions at about the same level
+///
+/// A pair is reported if the headings differ by 180° within `heading_tol_deg` and the
+/// altitude difference is within `alt_band_ft`.
+pub fn reciprocal_traffic(
+    planes: &[Airplane],
+    heading_tol_deg: f64,
+    alt_band_ft: f64,
+) -> Vec<(String, String)> {
+    let mut pairs = Vec::new();
+    for (i, plane1) in planes.iter().enumerate() {
+        for plane2 in &planes[i + 1..] {
+            let heading_diff = heading_difference_deg(plane1.heading, plane2.heading);
+            if 180.0 - heading_diff <= heading_tol_deg
+                && altitude_diff_ft(plane1, plane2) <= alt_band_ft
+            {
+                pairs.push((plane1.callsign.clone(), plane2.callsign.clone()));
+            }
+        }
+    }
+    pairs
+}
+
+/// Detect callsign handoffs between two snapshots
+///
+/// Each callsign that disappeared from `previous` is paired with the nearest callsign that
+/// newly appeared in `current`, provided it is within `max_gap_nm`. Every new callsign is
+/// used for at most one handoff.
+pub fn detect_handoffs(
+    previous: &[Airplane],
+    current: &[Airplane],
+    max_gap_nm: f64,
+) -> Vec<(String, String)> {
+    let previous_callsigns: HashSet<&str> = previous.iter().map(|p| p.callsign.as_str()).collect();
+    let current_callsigns: HashSet<&str> = current.iter().map(|p| p.callsign.as_str()).collect();
+
+    let mut appeared: Vec<&Airplane> = current
+        .iter()
+        .filter(|p| !previous_callsigns.contains(p.callsign.as_str()))
+        .collect();
+
+    let mut handoffs = Vec::new();
+    for vanished in previous.iter().filter(|p| !current_callsigns.contains(p.callsign.as_str())) {
+        let nearest = appeared
+            .iter()
+            .enumerate()
+            .map(|(index, candidate)| {
+                let gap = haversine_distance_nm(
+                    vanished.latitude,
+                    vanished.longitude,
+                    candidate.latitude,
+                    candidate.longitude,
+                );
+                (index, gap)
+            })
+            .filter(|(_, gap)| *gap <= max_gap_nm)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+
+        if let Some((index, _)) = nearest {
+            let successor = appeared.remove(index);
+            handoffs.push((vanished.callsign.clone(), successor.callsign.clone()));
+        }
+    }
+    handoffs
+}
+
+const ALERT_CSV_HEADER: &str =
+    "plane1_callsign,plane2_callsign,distance_nm,altitude_diff_ft,severity,geometry,predicted";
+
+/// Export alerts as CSV with a header row
+pub fn alerts_to_csv(alerts: &[Alert]) -> String {
+    let mut csv = String::from(ALERT_CSV_HEADER);
+    csv.push('\n');
+    for alert in alerts {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            alert.plane1_callsign,
+            alert.plane2_callsign,
+            alert.distance_nm,
+            alert.altitude_diff_ft,
+            alert.severity.as_str(),
+            alert.geometry.as_str(),
+            alert.predicted
+        ));
+    }
+    csv
+}
+
+/// Parse alerts from CSV as produced by [`alerts_to_csv`]
+pub fn alerts_from_csv(csv: &str) -> Result<Vec<Alert>, CsvError> {
+    let mut lines = csv.lines().enumerate();
+    match lines.next() {
+        Some((_, header)) if header.trim() == ALERT_CSV_HEADER => {}
+        _ => {
+            return Err(CsvError { line: 1, message: "missing or unexpected header".to_string() });
+        }
+    }
+
+    let mut alerts = Vec::new();
+    for (index, line) in lines {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let line_number = index + 1;
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        if fields.len() != 7 {
+            return Err(CsvError {
+                line: line_number,
+                message: format!("expected 7 fields, found {}", fields.len()),
+            });
+        }
+        let parse_number = |value: &str, name: &str| {
+            value.parse::<f64>().map_err(|_| CsvError {
+                line: line_number,
+                message: format!("invalid {name} '{value}'"),
+            })
+        };
+        alerts.push(Alert {
+            plane1_callsign: fields[0].to_string(),
+            plane2_callsign: fields[1].to_string(),
+            distance_nm: parse_number(fields[2], "distance_nm")?,
+            altitude_diff_ft: parse_number(fields[3], "altitude_diff_ft")?,
+            severity: AlertSeverity::parse(fields[4]).ok_or_else(|| CsvError {
+                line: line_number,
+                message: format!("invalid severity '{}'", fields[4]),
+            })?,
+            geometry: ConflictGeometry::parse(fields[5]).ok_or_else(|| CsvError {
+                line: line_number,
+                message: format!("invalid geometry '{}'", fields[5]),
+            })?,
+            predicted: fields[6].parse().map_err(|_| CsvError {
+                line: line_number,
+                message: format!("invalid predicted flag '{}'", fields[6]),
+            })?,
+        });
+    }
+    Ok(alerts)
+}
+
+/// Wake turbulence category for an ICAO aircraft type designator (unknown types are Medium)
+pub fn wake_category(aircraft_type: &str) -> WakeCategory {
+    match aircraft_type {
+        "A388" | "A225" => WakeCategory::Super,
+        "A332" | "A333" | "A339" | "A343" | "A346" | "A359" | "A35K" | "B744" | "B748"
+        | "B763" | "B772" | "B77L" | "B77W" | "B788" | "B789" | "B78X" => WakeCategory::Heavy,
+        "C152" | "C172" | "C182" | "PA28" | "SR22" | "DA40" | "DA42" | "BE20" => {
+            WakeCategory::Light
+        }
+        _ => WakeCategory::Medium,
+    }
+}
+
+/// Service ceiling used for aircraft types without a specific entry (feet)
+const DEFAULT_SERVICE_CEILING_FT: f64 = 41000.0;
+
+/// Maximum operating altitude in feet for an ICAO aircraft type designator
+///
+/// Unknown types use a typical jet ceiling of 41000ft.
+pub fn service_ceiling_ft(aircraft_type: &str) -> f64 {
+    match aircraft_type {
+        "AT76" | "AT75" | "AT72" => 25000.0,
+        "DH8D" => 27000.0,
+        "A320" | "A321" | "A319" => 39800.0,
+        "A359" | "A35K" | "B77W" => 43100.0,
+        "B788" | "B789" | "B78X" => 43000.0,
+        _ => DEFAULT_SERVICE_CEILING_FT,
+    }
+}
+
+/// Required radar separation in nautical miles for a follower behind a leader
+fn wake_separation_nm(leader: WakeCategory, follower: WakeCategory) -> Option<f64> {
+    use WakeCategory::*;
+    match (leader, follower) {
+        (Super, Heavy) => Some(6.0),
+        (Super, Medium) => Some(7.0),
+        (Super, Light) => Some(8.0),
+        (Heavy, Medium) => Some(5.0),
+        (Heavy, Light) => Some(6.0),
+        (Medium, Light) => Some(5.0),
+        _ => None,
+    }
+}
+
+/// Whether `follower` is flying behind `leader` in roughly the same direction
+fn is_trailing(leader: &Airplane, follower: &Airplane) -> bool {
+    let bearing_to_follower = initial_bearing_deg(
+        leader.latitude,
+        leader.longitude,
+        follower.latitude,
+        follower.longitude,
+    );
+    heading_difference_deg(bearing_to_follower, leader.heading + 180.0) <= 30.0
+        && heading_difference_deg(leader.heading, follower.heading) <= 45.0
+}
+
+/// Find lighter planes trailing heavier ones closer than the wake turbulence separation
+///
+/// In the returned alerts, `plane1` is the leading (heavier) and `plane2` the trailing plane.
+pub fn wake_separation_alerts(planes: &[Airplane]) -> Vec<Alert> {
+    let mut alerts = Vec::new();
+    for (i, leader) in planes.iter().enumerate() {
+        for (j, follower) in planes.iter().enumerate() {
+            let leader_category = wake_category(&leader.aircraft_type);
+            let follower_category = wake_category(&follower.aircraft_type);
+            if i == j || follower_category >= leader_category {
+                continue;
+            }
+            let Some(required_nm) = wake_separation_nm(leader_category, follower_category) else {
+                continue;
+            };
+            let altitude_diff_ft = altitude_diff_ft(leader, follower);
+            if altitude_diff_ft >= ALERT_ALTITUDE_DIFF_FT || !is_trailing(leader, follower) {
+                continue;
+            }
+            let distance_nm = horizontal_distance_nm(leader, follower);
+            if distance_nm < required_nm {
+                alerts.push(Alert::between(leader, follower, distance_nm, altitude_diff_ft));
+            }
+        }
+    }
+    alerts
+}
+
+/// Velocity of a plane as (north, east) components in knots
+fn velocity_components_kn(plane: &Airplane) -> (f64, f64) {
+    let heading = plane.heading.to_radians();
+    (plane.speed * heading.cos(), plane.speed * heading.sin())
+}
+
+/// Velocity of `plane2` relative to `plane1` as (north, east) components in knots
+pub fn relative_velocity_kn(plane1: &Airplane, plane2: &Airplane) -> (f64, f64) {
+    let (north1, east1) = velocity_components_kn(plane1);
+    let (north2, east2) = velocity_components_kn(plane2);
+    (north2 - north1, east2 - east1)
+}
+
+/// Count alerts per severity (every severity is present, possibly with a count of zero)
+pub fn severity_histogram(planes: &[Airplane]) -> HashMap<AlertSeverity, usize> {
+    let mut histogram: HashMap<AlertSeverity, usize> =
+        AlertSeverity::ALL.into_iter().map(|severity| (severity, 0)).collect();
+    for alert in check_all_alerts(planes) {
+        *histogram.entry(alert.severity).or_default() += 1;
+    }
+    histogram
+}
+
+/// Altitude below which the low-level speed limit applies (feet)
+const SPEED_LIMIT_ALTITUDE_FT: f64 = 10000.0;
+/// Maximum speed below [`SPEED_LIMIT_ALTITUDE_FT`] (knots)
+const SPEED_LIMIT_KN: f64 = 250.0;
+
+/// Find planes below 10,000ft flying faster than 250kn
+pub fn speed_limit_violations(planes: &[Airplane]) -> Vec<&Airplane> {
+    planes
+        .iter()
+        .filter(|p| p.altitude < SPEED_LIMIT_ALTITUDE_FT && p.speed > SPEED_LIMIT_KN)
+        .collect()
+}
+
+/// Round a value to the given number of decimal places
+fn round_to(value: f64, decimals: u32) -> f64 {
+    let factor = 10f64.powi(decimals as i32);
+    (value * factor).round() / factor
+}
+
+/// Round positions and altitudes to reduce the size of serialized snapshots
+pub fn round_snapshot(planes: &[Airplane], coord_decimals: u32, alt_decimals: u32) -> Vec<Airplane> {
+    planes
+        .iter()
+        .map(|plane| {
+            let mut rounded = plane.clone();
+            rounded.latitude = round_to(plane.latitude, coord_decimals);
+            rounded.longitude = round_to(plane.longitude, coord_decimals);
+            rounded.altitude = round_to(plane.altitude, alt_decimals);
+            rounded
+        })
+        .collect()
+}
+
+/// Mean position of all planes, or `None` for an empty slice
+///
+/// Longitude is averaged via its unit vector components so traffic across the antimeridian
+/// is centered correctly.
+pub fn traffic_centroid(planes: &[Airplane]) -> Option<(f64, f64)> {
+    if planes.is_empty() {
+        return None;
+    }
+    let count = planes.len() as f64;
+    let latitude = planes.iter().map(|p| p.latitude).sum::<f64>() / count;
+    let (sin_sum, cos_sum) = planes.iter().fold((0.0, 0.0), |(sin_sum, cos_sum), p| {
+        let lng = p.longitude.to_radians();
+        (sin_sum + lng.sin(), cos_sum + lng.cos())
+    });
+    Some((latitude, sin_sum.atan2(cos_sum).to_degrees()))
+}
+
+/// Conflict graph as adjacency list: each involved callsign maps to its conflicting neighbors
+pub fn alert_graph(planes: &[Airplane]) -> HashMap<String, Vec<String>> {
+    let mut graph: HashMap<String, Vec<String>> = HashMap::new();
+    for alert in check_all_alerts(planes) {
+        graph
+            .entry(alert.plane1_callsign.clone())
+            .or_default()
+            .push(alert.plane2_callsign.clone());
+        graph.entry(alert.plane2_callsign).or_default().push(alert.plane1_callsign);
+    }
+    graph
+}
+
+/// Explicit simulation clock, decoupled from wall time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimClock {
+    /// Current scenario time in seconds
+    pub time_s: f64,
+    /// Time advanced by each call to [`SimClock::step`] in seconds
+    pub dt_s: f64,
+}
+
+impl SimClock {
+    pub fn new(dt_s: f64) -> Self {
+        SimClock { time_s: 0.0, dt_s }
+    }
+
+    pub fn advance(&mut self, dt_s: f64) {
+        self.time_s += dt_s;
+    }
+
+    /// Advance the clock by `dt_s` and move the planes accordingly
+    ///
+    /// The moved planes are stamped with the new clock time.
+    pub fn step(&mut self, planes: Vec<Airplane>) -> Vec<Airplane> {
+        self.advance(self.dt_s);
+        let mut planes = calculate_airplane_positions(&planes, self.dt_s);
+        planes.iter_mut().for_each(|plane| plane.last_update_s = self.time_s);
+        planes
+    }
+}
+
+/// Find planes whose heading changed faster than `threshold_deg_per_s` between two snapshots
+///
+/// Planes are matched by callsign and the heading change uses the shortest arc. Returns
+/// nothing unless `dt_s` is positive and finite.
+pub fn detect_turning(
+    previous: &[Airplane],
+    current: &[Airplane],
+    dt_s: f64,
+    threshold_deg_per_s: f64,
+) -> Vec<String> {
+    if !dt_s.is_finite() || dt_s <= 0.0 {
+        return Vec::new();
+    }
+    let previous_by_callsign: HashMap<&str, &Airplane> =
+        previous.iter().map(|p| (p.callsign.as_str(), p)).collect();
+    current
+        .iter()
+        .filter(|plane| {
+            previous_by_callsign.get(plane.callsign.as_str()).is_some_and(|before| {
+                heading_difference_deg(before.heading, plane.heading) / dt_s > threshold_deg_per_s
+            })
+        })
+        .map(|plane| plane.callsign.clone())
+        .collect()
+}
+
+/// Simulate `steps` position updates and return the cumulative distance flown per callsign
+pub fn simulate_with_odometer(
+    initial: &[Airplane],
+    step_s: f64,
+    steps: usize,
+) -> HashMap<String, f64> {
+    let mut odometer: HashMap<String, f64> =
+        initial.iter().map(|p| (p.callsign.clone(), 0.0)).collect();
+    let mut planes = initial.to_vec();
+    for _ in 0..steps {
+        let next = calculate_airplane_positions(&planes, step_s);
+        for (before, after) in planes.iter().zip(&next) {
+            *odometer.entry(before.callsign.clone()).or_default() +=
+                horizontal_distance_nm(before, after);
+        }
+        planes = next;
+    }
+    odometer
+}
+
+type Vec3 = [f64; 3];
+
+fn cross(a: Vec3, b: Vec3) -> Vec3 {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn dot(a: Vec3, b: Vec3) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Unit vector of a lat/lng position on the sphere
+fn to_unit_vector(lat: f64, lng: f64) -> Vec3 {
+    let (lat, lng) = (lat.to_radians(), lng.to_radians());
+    [lat.cos() * lng.cos(), lat.cos() * lng.sin(), lat.sin()]
+}
+
+/// Normal of the great circle through the plane's position along its heading
+fn track_normal(plane: &Airplane) -> Vec3 {
+    let (lat, lng) = (plane.latitude.to_radians(), plane.longitude.to_radians());
+    let heading = plane.heading.to_radians();
+    let east = [-lng.sin(), lng.cos(), 0.0];
+    let north = [-lat.sin() * lng.cos(), -lat.sin() * lng.sin(), lat.cos()];
+    let direction = [
+        north[0] * heading.cos() + east[0] * heading.sin(),
+        north[1] * heading.cos() + east[1] * heading.sin(),
+        north[2] * heading.cos() + east[2] * heading.sin(),
+    ];
+    cross(to_unit_vector(plane.latitude, plane.longitude), direction)
+}
+
+/// Point where the great-circle tracks of two planes cross
+///
+/// Of the two antipodal intersections, the one on the same side of the earth as `plane1` is
+/// returned. Returns `None` for parallel (same or reciprocal) headings and for planes on the
+/// same great circle.
+pub fn track_intersection(plane1: &Airplane, plane2: &Airplane) -> Option<(f64, f64)> {
+    const EPSILON: f64 = 1e-9;
+    let heading_diff = heading_difference_deg(plane1.heading, plane2.heading);
+    if heading_diff < EPSILON || 180.0 - heading_diff < EPSILON {
+        return None;
+    }
+
+    let mut intersection = cross(track_normal(plane1), track_normal(plane2));
+    let norm = dot(intersection, intersection).sqrt();
+    if norm < EPSILON {
+        return None;
+    }
+    if dot(intersection, to_unit_vector(plane1.latitude, plane1.longitude)) < 0.0 {
+        intersection = intersection.map(|c| -c);
+    }
+
+    let [x, y, z] = intersection.map(|c| c / norm);
+    Some((z.asin().to_degrees(), y.atan2(x).to_degrees()))
+}
+
+/// The `n` most urgent alerts (smallest horizontal distance first)
+pub fn top_alerts(planes: &[Airplane], n: usize) -> Vec<Alert> {
+    let mut alerts = check_all_alerts(planes);
+    alerts.sort_by(|a, b| a.distance_nm.total_cmp(&b.distance_nm));
+    alerts.truncate(n);
+    alerts
+}
+
+/// Group planes by sector of their bearing from `center` (sector index = bearing / sector_deg)
+pub fn bin_by_bearing_from(
+    center: (f64, f64),
+    planes: &[Airplane],
+    sector_deg: f64,
+) -> BTreeMap<u32, Vec<&Airplane>> {
+    let mut bins: BTreeMap<u32, Vec<&Airplane>> = BTreeMap::new();
+    for plane in planes {
+        let bearing = initial_bearing_deg(center.0, center.1, plane.latitude, plane.longitude);
+        let sector = (bearing / sector_deg).floor() as u32;
+        bins.entry(sector).or_default().push(plane);
+    }
+    bins
+}
+
+/// Offset from the first to the second position as (north, east) nautical miles
+///
+/// Flat-earth approximation, accurate for the distances of the simulated airspace.
+fn local_offset_nm(from_lat: f64, from_lng: f64, to_lat: f64, to_lng: f64) -> (f64, f64) {
+    let mean_lat = ((from_lat + to_lat) / 2.0).to_radians();
+    (
+        (to_lat - from_lat) * math::NM_PER_DEGREE,
+        (to_lng - from_lng) * math::NM_PER_DEGREE * mean_lat.cos(),
+    )
+}
+
+/// Heading the pursuer has to fly to intercept the target on its current track
+///
+/// Both planes are assumed to keep their speed; the target also keeps its heading. Returns
+/// `None` if no interception is possible (e.g. because the pursuer is too slow).
+pub fn intercept_heading(pursuer: &Airplane, target: &Airplane) -> Option<f64> {
+    let (north, east) =
+        local_offset_nm(pursuer.latitude, pursuer.longitude, target.latitude, target.longitude);
+    let (target_north, target_east) = velocity_components_kn(target);
+
+    // Solve |r + v_target * t| = v_pursuer * t for the earliest t > 0 (hours)
+    let a = target_north.powi(2) + target_east.powi(2) - pursuer.speed.powi(2);
+    let b = 2.0 * (north * target_north + east * target_east);
+    let c = north.powi(2) + east.powi(2);
+
+    let time_h = if a.abs() < 1e-9 {
+        // Same speed: the equation is linear
+        if b >= 0.0 {
+            return None;
+        }
+        -c / b
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let sqrt_d = discriminant.sqrt();
+        [(-b - sqrt_d) / (2.0 * a), (-b + sqrt_d) / (2.0 * a)]
+            .into_iter()
+            .filter(|t| *t > 0.0)
+            .min_by(f64::total_cmp)?
+    };
+
+    let intercept_north = north + target_north * time_h;
+    let intercept_east = east + target_east * time_h;
+    Some(intercept_east.atan2(intercept_north).to_degrees().rem_euclid(360.0))
+}
+
+/// Whether a position lies inside a polygon of (lat, lng) vertices (ray casting)
+pub fn point_in_polygon(lat: f64, lng: f64, polygon: &[(f64, f64)]) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len().wrapping_sub(1);
+    for (i, &(lat_i, lng_i)) in polygon.iter().enumerate() {
+        let (lat_j, lng_j) = polygon[j];
+        if (lat_i > lat) != (lat_j > lat)
+            && lng < (lng_j - lng_i) * (lat - lat_i) / (lat_j - lat_i) + lng_i
+        {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Predict which planes currently outside a sector will enter it within `horizon_s`
+///
+/// Positions are sampled every `step_s` seconds; the returned time is the first sample
+/// inside the sector. Returns nothing unless `step_s` is positive and both values are finite.
+pub fn sector_entry_predictions(
+    planes: &[Airplane],
+    polygon: &[(f64, f64)],
+    horizon_s: f64,
+    step_s: f64,
+) -> Vec<(String, f64)> {
+    if !(step_s.is_finite() && horizon_s.is_finite()) || step_s <= 0.0 {
+        return Vec::new();
+    }
+    planes
+        .iter()
+        .filter(|plane| !point_in_polygon(plane.latitude, plane.longitude, polygon))
+        .filter_map(|plane| {
+            (1..)
+                .map(|step| step as f64 * step_s)
+                .take_while(|t| *t <= horizon_s)
+                .find(|t| {
+                    let future = PositionModel::default().advance(plane, *t);
+                    point_in_polygon(future.latitude, future.longitude, polygon)
+                })
+                .map(|t| (plane.callsign.clone(), t))
+        })
+        .collect()
+}
+
+/// Number of non-conflicting background planes in a generated conflict scenario
+const CONFLICT_SCENARIO_BACKGROUND_PLANES: usize = 10;
+
+/// Generate a reproducible scenario with `n_conflicts` conflicting pairs plus background traffic
+///
+/// Each pair is placed within 2nm and 500ft of each other, below the service ceiling of both
+/// aircraft types. Background planes are rejected
+/// and re-drawn until they don't conflict with any other plane at the scenario start.
+pub fn generate_conflict_scenario(n_conflicts: usize, seed: u64) -> Vec<Airplane> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let config = GeneratorConfig::default();
+    let mut used = HashSet::new();
+    let mut planes = Vec::new();
+
+    for _ in 0..n_conflicts {
+        let mut first = random_airplane(&mut rng, &mut used, planes.len() as u64 + 1, &config);
+        let mut second = random_airplane(&mut rng, &mut used, planes.len() as u64 + 2, &config);
+        let ceiling_ft =
+            service_ceiling_ft(&first.aircraft_type).min(service_ceiling_ft(&second.aircraft_type));
+        first.altitude = first.altitude.min(ceiling_ft);
+        (second.latitude, second.longitude) = offset_position_along(
+            first.latitude,
+            first.longitude,
+            rng.random_range(0.0..360.0),
+            rng.random_range(0.0..2.0),
+        );
+        second.altitude =
+            (first.altitude + rng.random_range(-500.0..500.0_f64).round()).min(ceiling_ft);
+        planes.push(first);
+        planes.push(second);
+    }
+
+    let mut background = 0;
+    while background < CONFLICT_SCENARIO_BACKGROUND_PLANES {
+        let candidate = random_airplane(&mut rng, &mut used, planes.len() as u64 + 1, &config);
+        if planes.iter().all(|p| check_alert_between_planes(p, &candidate).is_none()) {
+            planes.push(candidate);
+            background += 1;
+        }
+    }
+
+    planes
+}
+
+/// Count planes per cell of a `rows` x `cols` grid over the given bounding box
+///
+/// Row 0 is the southernmost row and column 0 the westernmost column. Planes outside the
+/// box are ignored.
+pub fn density_grid(
+    planes: &[Airplane],
+    min_lat: f64,
+    min_lng: f64,
+    max_lat: f64,
+    max_lng: f64,
+    rows: usize,
+    cols: usize,
+) -> Vec<Vec<usize>> {
+    let mut grid = vec![vec![0; cols]; rows];
+    if rows == 0 || cols == 0 {
+        return grid;
+    }
+    let cell_height = (max_lat - min_lat) / rows as f64;
+    let cell_width = (max_lng - min_lng) / cols as f64;
+    for plane in planes {
+        if !(min_lat..=max_lat).contains(&plane.latitude)
+            || !(min_lng..=max_lng).contains(&plane.longitude)
+        {
+            continue;
+        }
+        // Planes exactly on the northern/eastern edge belong to the last row/column
+        let row = (((plane.latitude - min_lat) / cell_height) as usize).min(rows - 1);
+        let col = (((plane.longitude - min_lng) / cell_width) as usize).min(cols - 1);
+        grid[row][col] += 1;
+    }
+    grid
+}
+
+/// Distance from the destination (nm) at which the plane has to start descending
+///
+/// `glide_ratio` is given in nautical miles per 1000ft of altitude (the "3:1 rule" uses 3.0).
+/// The destination is assumed to be at sea level, so its position does not change the result.
+pub fn descent_profile(plane: &Airplane, _dest_lat: f64, _dest_lng: f64, glide_ratio: f64) -> f64 {
+    plane.altitude / 1000.0 * glide_ratio
+}
+
+/// Field order of the positional arrays in the compact snapshot format
+const COMPACT_FIELDS: [&str; 7] =
+    ["callsign", "aircraft_type", "latitude", "longitude", "altitude", "speed", "heading"];
+
+type CompactPlane = (String, String, f64, f64, f64, f64, f64);
+
+#[derive(Serialize, Deserialize)]
+struct CompactSnapshot {
+    fields: Vec<String>,
+    planes: Vec<CompactPlane>,
+}
+
+/// Serialize planes as positional arrays with a header describing the field order
+///
+/// Example: `{"fields":["callsign",...],"planes":[["AAL123","A320",48.2,14.1,30000.0,250.0,90.0]]}`
+pub fn snapshot_to_compact_json(planes: &[Airplane]) -> String {
+    let snapshot = CompactSnapshot {
+        fields: COMPACT_FIELDS.iter().map(|f| f.to_string()).collect(),
+        planes: planes
+            .iter()
+            .map(|p| {
+                (
+                    p.callsign.clone(),
+                    p.aircraft_type.clone(),
+                    p.latitude,
+                    p.longitude,
+                    p.altitude,
+                    p.speed,
+                    p.heading,
+                )
+            })
+            .collect(),
+    };
+    serde_json::to_string(&snapshot).expect("compact snapshot is always serializable")
+}
+
+/// Parse planes from the format produced by [`snapshot_to_compact_json`]
+pub fn snapshot_from_compact_json(json: &str) -> Result<Vec<Airplane>, serde_json::Error> {
+    let snapshot: CompactSnapshot = serde_json::from_str(json)?;
+    if snapshot.fields != COMPACT_FIELDS {
+        return Err(serde::de::Error::custom(format!(
+            "unexpected field order {:?}",
+            snapshot.fields
+        )));
+    }
+    Ok(snapshot
+        .planes
+        .into_iter()
+        .map(|(callsign, aircraft_type, latitude, longitude, altitude, speed, heading)| {
+            Airplane::new(&callsign, &aircraft_type, latitude, longitude, altitude, speed, heading)
+        })
+        .collect())
+}
+
+/// Generate `per_hub` random planes around each hub with callsigns unique across all hubs
+pub fn generate_multi_hub(hubs: &[(f64, f64)], per_hub: usize, seed: u64) -> Vec<Airplane> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut used = HashSet::new();
+    let mut planes = Vec::with_capacity(hubs.len() * per_hub);
+    for &center in hubs {
+        let config = GeneratorConfig { center, ..GeneratorConfig::default() };
+        for _ in 0..per_hub {
+            let id = planes.len() as u64 + 1;
+            planes.push(random_airplane(&mut rng, &mut used, id, &config));
+        }
+    }
+    planes
+}
+
+/// Unordered key for the pair of callsigns of an alert
+fn alert_pair_key(alert: &Alert) -> (String, String) {
+    let (a, b) = (&alert.plane1_callsign, &alert.plane2_callsign);
+    if a <= b { (a.clone(), b.clone()) } else { (b.clone(), a.clone()) }
+}
+
+/// Remembers when conflicts were last reported to suppress repeats across snapshots
+#[derive(Debug, Clone, Default)]
+pub struct RecentAlerts {
+    last_seen_s: HashMap<(String, String), f64>,
+}
+
+impl RecentAlerts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remember the given alerts as seen at `now_s`
+    pub fn record(&mut self, alerts: &[Alert], now_s: f64) {
+        for alert in alerts {
+            self.last_seen_s.insert(alert_pair_key(alert), now_s);
+        }
+    }
+
+    /// Alerts whose pair (in either order) has not been recorded within the last `window_s`
+    pub fn novel(&self, alerts: &[Alert], now_s: f64, window_s: f64) -> Vec<Alert> {
+        alerts
+            .iter()
+            .filter(|alert| {
+                self.last_seen_s
+                    .get(&alert_pair_key(alert))
+                    .is_none_or(|seen_s| now_s - seen_s > window_s)
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// Signed great-circle cross-track distance from the plane to a route leg in nautical miles
+///
+/// Positive values mean the plane is right of the leg (seen in the direction of flight from
+/// `leg_start` to `leg_end`), negative values mean it is left of it.
+pub fn cross_track_distance_nm(
+    plane: &Airplane,
+    leg_start: (f64, f64),
+    leg_end: (f64, f64),
+) -> f64 {
+    let (start_lat, start_lng) = leg_start;
+    let angular_distance =
+        haversine_distance_nm(start_lat, start_lng, plane.latitude, plane.longitude)
+            / math::EARTH_RADIUS_NM;
+    let bearing_to_plane =
+        initial_bearing_deg(start_lat, start_lng, plane.latitude, plane.longitude).to_radians();
+    let bearing_of_leg = initial_bearing_deg(start_lat, start_lng, leg_end.0, leg_end.1).to_radians();
+    let cross_track = (angular_distance.sin() * (bearing_to_plane - bearing_of_leg).sin()).asin();
+    cross_track * math::EARTH_RADIUS_NM
+}
+
+/// Group planes flying in formation: mutually within `max_sep_nm` and on the same heading
+///
+/// Groups are built greedily in input order; every plane belongs to at most one group and
+/// only groups of at least two planes are returned.
+pub fn detect_formations(
+    planes: &[Airplane],
+    max_sep_nm: f64,
+    heading_tol_deg: f64,
+) -> Vec<Vec<String>> {
+    let in_formation = |a: &Airplane, b: &Airplane| {
+        horizontal_distance_nm(a, b) <= max_sep_nm
+            && heading_difference_deg(a.heading, b.heading) <= heading_tol_deg
+    };
+
+    let mut grouped = vec![false; planes.len()];
+    let mut formations = Vec::new();
+    for i in 0..planes.len() {
+        if grouped[i] {
+            continue;
+        }
+        let mut members = vec![i];
+        for (j, candidate) in planes.iter().enumerate().skip(i + 1) {
+            if !grouped[j] && members.iter().all(|&m| in_formation(&planes[m], candidate)) {
+                members.push(j);
+            }
+        }
+        if members.len() > 1 {
+            members.iter().for_each(|&m| grouped[m] = true);
+            formations.push(members.iter().map(|&m| planes[m].callsign.clone()).collect());
+        }
+        grouped[i] = true;
+    }
+    formations
+}
+
+/// Simulate `steps` position updates and report each step's alerts to `on_alerts`
+///
+/// The callback receives the 0-based step index and the alerts after moving the planes.
+/// Returns the planes after the last step.
+pub fn simulate_with_alert_callback(
+    initial: Vec<Airplane>,
+    step_s: f64,
+    steps: usize,
+    mut on_alerts: impl FnMut(usize, &[Alert]),
+) -> Vec<Airplane> {
+    let mut planes = initial;
+    for step in 0..steps {
+        planes = calculate_airplane_positions(&planes, step_s);
+        on_alerts(step, &check_all_alerts(&planes));
+    }
+    planes
+}
+
+/// Additional altitude difference (ft) needed to clear the vertical alert threshold
+///
+/// Returns 0 if the planes are already vertically separated.
+pub fn min_vertical_separation_needed_ft(plane1: &Airplane, plane2: &Airplane) -> f64 {
+    (ALERT_ALTITUDE_DIFF_FT - altitude_diff_ft(plane1, plane2)).max(0.0)
+}
+
+/// A loss of separation between two planes over a period of a replay
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LosEvent {
+    pub plane1_callsign: String,
+    pub plane2_callsign: String,
+    /// Time of the first snapshot with loss of separation
+    pub start_s: f64,
+    /// Time of the last snapshot with loss of separation
+    pub end_s: f64,
+    /// Smallest horizontal distance reached during the event
+    pub min_distance_nm: f64,
+}
+
+/// Find loss-of-separation events in a sequence of timestamped snapshots
+///
+/// An event lasts as long as the pair alerts in consecutive snapshots. Events are returned
+/// in the order they end.
+pub fn analyze_replay(snapshots: &[(f64, Vec<Airplane>)], config: &AlertConfig) -> Vec<LosEvent> {
+    let mut open: HashMap<(String, String), LosEvent> = HashMap::new();
+    let mut events = Vec::new();
+
+    for (time_s, planes) in snapshots {
+        let alerts = check_all_alerts_with(planes, config, horizontal_distance_nm);
+        let mut active = HashSet::new();
+        for alert in &alerts {
+            let key = alert_pair_key(alert);
+            open.entry(key.clone())
+                .and_modify(|event| {
+                    event.end_s = *time_s;
+                    event.min_distance_nm = event.min_distance_nm.min(alert.distance_nm);
+                })
+                .or_insert_with(|| LosEvent {
+                    plane1_callsign: key.0.clone(),
+                    plane2_callsign: key.1.clone(),
+                    start_s: *time_s,
+                    end_s: *time_s,
+                    min_distance_nm: alert.distance_nm,
+                });
+            active.insert(key);
+        }
+
+        let mut ended: Vec<_> =
+            open.keys().filter(|key| !active.contains(*key)).cloned().collect();
+        ended.sort();
+        for key in ended {
+            events.extend(open.remove(&key));
+        }
+    }
+
+    let mut remaining: Vec<_> = open.into_iter().collect();
+    remaining.sort_by(|a, b| a.0.cmp(&b.0));
+    events.extend(remaining.into_iter().map(|(_, event)| event));
+    events
+}
+
+/// `n` points evenly spaced along the great circle from `start` to `end` (both included)
+pub fn sample_great_circle(start: (f64, f64), end: (f64, f64), n: usize) -> Vec<(f64, f64)> {
+    match n {
+        0 => Vec::new(),
+        1 => vec![start],
+        _ => (0..n)
+            .map(|i| great_circle_intermediate(start, end, i as f64 / (n - 1) as f64))
+            .collect(),
+    }
+}
+
+/// Number of Monte Carlo samples drawn by `conflict_probability`
+const CONFLICT_PROBABILITY_SAMPLES: usize = 500;
+/// Time step when searching a sampled encounter for a loss of separation (seconds)
+const CONFLICT_PROBABILITY_STEP_S: f64 = 10.0;
+
+/// Estimate the probability that two planes lose separation within `horizon_s` seconds
+///
+/// Both positions are perturbed with Gaussian noise of `pos_sigma_nm` per axis and the
+/// perturbed pair is flown forward in fixed steps. The result is the fraction of samples that
+/// breach the alert box and is deterministic for a given seed. Returns 0 unless `horizon_s` is
+/// finite and not negative.
+pub fn conflict_probability(
+    plane1: &Airplane,
+    plane2: &Airplane,
+    pos_sigma_nm: f64,
+    horizon_s: f64,
+    seed: u64,
+) -> f64 {
+    if !horizon_s.is_finite() || horizon_s < 0.0 {
+        return 0.0;
+    }
+    let mut rng = StdRng::seed_from_u64(seed);
+    let steps = (horizon_s / CONFLICT_PROBABILITY_STEP_S).ceil().max(0.0) as usize;
+    let perturb = |rng: &mut StdRng, plane: &Airplane| {
+        let north_nm = sample_standard_normal(rng) * pos_sigma_nm;
+        let east_nm = sample_standard_normal(rng) * pos_sigma_nm;
+        let mut sampled = plane.clone();
+        (sampled.latitude, sampled.longitude) =
+            offset_position(plane.latitude, plane.longitude, north_nm, east_nm);
+        sampled
+    };
+
+    let model = PositionModel::default();
+    let conflicts = (0..CONFLICT_PROBABILITY_SAMPLES)
+        .filter(|_| {
+            let sample1 = perturb(&mut rng, plane1);
+            let sample2 = perturb(&mut rng, plane2);
+            let altitude_diff = altitude_diff_ft(&sample1, &sample2);
+            (0..=steps).any(|step| {
+                let elapsed = (step as f64 * CONFLICT_PROBABILITY_STEP_S).min(horizon_s);
+                let distance = horizontal_distance_nm(
+                    &model.advance(&sample1, elapsed),
+                    &model.advance(&sample2, elapsed),
+                );
+                is_loss_of_separation(distance, altitude_diff)
+            })
+        })
+        .count();
+    conflicts as f64 / CONFLICT_PROBABILITY_SAMPLES as f64
+}
+
+/// Collapse alerts reported more than once for the same pair (in either order)
+///
+/// The most severe alert of each pair is kept; ties go to the smaller horizontal distance.
+/// Pairs stay in the order of their first appearance.
+pub fn merge_alerts(alerts: Vec<Alert>) -> Vec<Alert> {
+    let mut merged: Vec<Alert> = Vec::with_capacity(alerts.len());
+    let mut index_by_pair: HashMap<(String, String), usize> = HashMap::new();
+    for alert in alerts {
+        match index_by_pair.get(&alert_pair_key(&alert)) {
+            Some(&index) => {
+                let kept = &mut merged[index];
+                let more_urgent = alert.severity > kept.severity
+                    || (alert.severity == kept.severity && alert.distance_nm < kept.distance_nm);
+                if more_urgent {
+                    *kept = alert;
+                }
+            }
+            None => {
+                index_by_pair.insert(alert_pair_key(&alert), merged.len());
+                merged.push(alert);
+            }
+        }
+    }
+    merged
+}
+
+/// Look up planes by callsign; if a callsign appears more than once the last plane wins
+pub fn index_by_callsign(planes: &[Airplane]) -> HashMap<&str, &Airplane> {
+    planes.iter().map(|plane| (plane.callsign.as_str(), plane)).collect()
+}
+
+/// Great-circle distance still to fly along a route in nautical miles
+///
+/// Leg `i` runs from `route[i]` to `route[i + 1]`. The result is the distance from the plane
+/// to the end of `current_leg` plus the length of all later legs; it is `0.0` once
+/// `current_leg` is past the last leg.
+pub fn distance_remaining_nm(plane: &Airplane, route: &[(f64, f64)], current_leg: usize) -> f64 {
+    let Some(&(leg_end_lat, leg_end_lng)) = route.get(current_leg + 1) else {
+        return 0.0;
+    };
+    let to_leg_end =
+        haversine_distance_nm(plane.latitude, plane.longitude, leg_end_lat, leg_end_lng);
+    let later_legs: f64 = route[current_leg + 1..]
+        .windows(2)
+        .map(|leg| haversine_distance_nm(leg[0].0, leg[0].1, leg[1].0, leg[1].1))
+        .sum();
+    to_leg_end + later_legs
+}
+
+/// Prometheus text exposition of the current traffic picture
+///
+/// `flight_min_separation_nm` is the smallest horizontal distance between any two planes and
+/// is left out when there are fewer than two planes.
+pub fn metrics_text(planes: &[Airplane]) -> String {
+    let mut text = format!(
+        "flight_plane_count {}\nflight_alert_count {}\n",
+        planes.len(),
+        check_all_alerts(planes).len()
+    );
+    if let Some((_, _, min_separation)) = closest_pair(planes) {
+        text.push_str(&format!("flight_min_separation_nm {}\n", round_to(min_separation, 3)));
+    }
+    text
+}
+
+/// Traffic simulation that can be paused while conflicts keep being evaluated
+#[derive(Debug, Clone, Default)]
+pub struct Simulation {
+    pub planes: Vec<Airplane>,
+    /// Scenario time in seconds; does not advance while paused
+    pub time_s: f64,
+    /// When set, [`Simulation::tick`] leaves the planes where they are
+    pub paused: bool,
+}
+
+impl Simulation {
+    pub fn new(planes: Vec<Airplane>) -> Self {
+        Simulation { planes, time_s: 0.0, paused: false }
+    }
+
+    /// Move the planes by `dt_s` seconds unless paused and return the current alerts
+    pub fn tick(&mut self, dt_s: f64) -> Vec<Alert> {
+        if !self.paused {
+            self.planes = calculate_airplane_positions(&self.planes, dt_s);
+            self.time_s += dt_s;
+            self.planes.iter_mut().for_each(|plane| plane.last_update_s = self.time_s);
+        }
+        check_all_alerts(&self.planes)
+    }
+}
+
+/// Planes deviating from their assigned altitude by more than `tol_ft`
+///
+/// Returns the callsign and the signed deviation (actual minus assigned, negative means below
+/// the assigned level). Planes without an assigned altitude are ignored.
+pub fn altitude_deviations(planes: &[Airplane], tol_ft: f64) -> Vec<(String, f64)> {
+    planes
+        .iter()
+        .filter_map(|plane| {
+            let deviation = plane.altitude - plane.assigned_altitude_ft?;
+            (deviation.abs() > tol_ft).then(|| (plane.callsign.clone(), deviation))
+        })
+        .collect()
+}
+
+/// The two planes with the smallest horizontal separation and their distance in nautical miles
+///
+/// Altitude is ignored. Returns `None` for fewer than two planes.
+pub fn closest_pair(planes: &[Airplane]) -> Option<(String, String, f64)> {
+    planes
+        .iter()
+        .enumerate()
+        .flat_map(|(i, plane1)| {
+            planes[i + 1..]
+                .iter()
+                .map(move |plane2| (plane1, plane2, horizontal_distance_nm(plane1, plane2)))
+        })
+        .min_by(|a, b| a.2.total_cmp(&b.2))
+        .map(|(plane1, plane2, distance)| {
+            (plane1.callsign.clone(), plane2.callsign.clone(), distance)
+        })
+}
+
+/// Rate at which the horizontal distance between two planes shrinks in knots
+///
+/// Positive while converging, negative while diverging and zero for co-located planes.
+fn closure_rate_kn(plane1: &Airplane, plane2: &Airplane) -> f64 {
+    let (north, east) =
+        local_offset_nm(plane1.latitude, plane1.longitude, plane2.latitude, plane2.longitude);
+    let range = north.hypot(east);
+    if range == 0.0 {
+        return 0.0;
+    }
+    let (v_north, v_east) = relative_velocity_kn(plane1, plane2);
+    -(north * v_north + east * v_east) / range
+}
+
+/// Weight of horizontal proximity in [`risk_score`]
+const RISK_WEIGHT_DISTANCE: f64 = 0.4;
+/// Weight of vertical proximity in [`risk_score`]
+const RISK_WEIGHT_ALTITUDE: f64 = 0.3;
+/// Weight of the closure rate in [`risk_score`]
+const RISK_WEIGHT_CLOSURE: f64 = 0.3;
+/// Closure rate at which the closure factor of [`risk_score`] saturates (knots)
+const RISK_MAX_CLOSURE_KN: f64 = 1000.0;
+
+/// Collision risk of a pair as a single score between 0 (harmless) and 1 (imminent)
+///
+/// Weighted sum of three factors in 0..=1: horizontal proximity `1 / (1 + d / 5nm)` (40%),
+/// vertical proximity `1 / (1 + dh / 1000ft)` (30%) and the closure rate relative to 1000kn
+/// (30%, zero while diverging).
+pub fn risk_score(plane1: &Airplane, plane2: &Airplane) -> f64 {
+    let distance = horizontal_distance_nm(plane1, plane2) / ALERT_DISTANCE_NM;
+    let altitude = altitude_diff_ft(plane1, plane2) / ALERT_ALTITUDE_DIFF_FT;
+    let closure = (closure_rate_kn(plane1, plane2) / RISK_MAX_CLOSURE_KN).clamp(0.0, 1.0);
+    RISK_WEIGHT_DISTANCE / (1.0 + distance)
+        + RISK_WEIGHT_ALTITUDE / (1.0 + altitude)
+        + RISK_WEIGHT_CLOSURE * closure
+}
+
+/// Relative traffic volume per hour of the day (local time), peaking in the morning and evening
+const DIURNAL_TRAFFIC_FACTORS: [f64; 24] = [
+    0.15, 0.1, 0.1, 0.1, 0.15, 0.3, 0.6, 0.9, 1.0, 0.9, 0.75, 0.7, // 00-11
+    0.7, 0.7, 0.7, 0.75, 0.85, 0.95, 1.0, 0.9, 0.7, 0.5, 0.35, 0.2, // 12-23
+];
+
+/// Generate random traffic (without the test planes) scaled by the typical volume at `hour`
+///
+/// `base_n` is the number of planes at peak time; hours above 23 wrap around.
+pub fn generate_for_hour(hour: u8, base_n: usize, seed: u64) -> Vec<Airplane> {
+    let factor = DIURNAL_TRAFFIC_FACTORS[hour as usize % 24];
+    generate_random_airplanes((base_n as f64 * factor).round() as usize, seed)
+}
+
+/// Seconds the follower needs to reach the leader's current position along the leader's track
+///
+/// Only the along-track component of the spacing counts. Returns `None` if the follower is
+/// level with or ahead of the leader, or not moving.
+pub fn time_separation_s(leader: &Airplane, follower: &Airplane) -> Option<f64> {
+    let (north, east) =
+        local_offset_nm(leader.latitude, leader.longitude, follower.latitude, follower.longitude);
+    let heading = leader.heading.to_radians();
+    let behind_nm = -(north * heading.cos() + east * heading.sin());
+    if behind_nm <= 0.0 || follower.speed <= 0.0 {
+        return None;
+    }
+    Some(behind_nm / follower.speed * 3600.0)
+}
+
+/// Ground elevation lookup used for terrain clearance checks
+pub trait TerrainModel {
+    /// Elevation of the ground at the given position in feet above mean sea level
+    fn elevation_ft(&self, lat: f64, lng: f64) -> f64;
+}
+
+/// Terrain with the same elevation everywhere (sea level by default)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FlatTerrain {
+    pub elevation_ft: f64,
+}
+
+impl TerrainModel for FlatTerrain {
+    fn elevation_ft(&self, _lat: f64, _lng: f64) -> f64 {
+        self.elevation_ft
+    }
+}
+
+/// Callsigns of planes flying lower than `clearance_ft` above the terrain below them
+pub fn terrain_conflicts(
+    planes: &[Airplane],
+    terrain: &impl TerrainModel,
+    clearance_ft: f64,
+) -> Vec<String> {
+    planes
+        .iter()
+        .filter(|plane| {
+            plane.altitude < terrain.elevation_ft(plane.latitude, plane.longitude) + clearance_ft
+        })
+        .map(|plane| plane.callsign.clone())
+        .collect()
+}
+
+/// Snapshot of the traffic at a point in time, as written by [`write_snapshot`]
+#[derive(Serialize)]
+struct TimedSnapshot<'a> {
+    time_s: f64,
+    planes: &'a [Airplane],
+}
+
+/// Stream a snapshot as JSON (`{"time_s": .., "planes": [..]}`) into `w`
+///
+/// The planes are serialized directly into the writer without building an intermediate
+/// `String`. Wrap unbuffered writers such as files or sockets in a `BufWriter`.
+pub fn write_snapshot<W: std::io::Write>(
+    w: W,
+    planes: &[Airplane],
+    now_s: f64,
+) -> std::io::Result<()> {
+    serde_json::to_writer(w, &TimedSnapshot { time_s: now_s, planes })?;
+    Ok(())
+}
+
+/// Classify how the tracks of two planes meet (see [`ConflictGeometry`])
+pub fn conflict_geometry(plane1: &Airplane, plane2: &Airplane) -> ConflictGeometry {
+    let difference = heading_difference_deg(plane1.heading, plane2.heading);
+    if difference > 135.0 {
+        ConflictGeometry::HeadOn
+    } else if difference >= 45.0 {
+        ConflictGeometry::Crossing
+    } else {
+        ConflictGeometry::Overtaking
+    }
+}
+
+/// Ground speed of planes built with [`test_plane`] in knots
+pub const TEST_PLANE_SPEED_KN: f64 = 250.0;
+/// Aircraft type of planes built with [`test_plane`]
+pub const TEST_PLANE_TYPE: &str = "A320";
+
+/// Build a plane at a fixed position with default speed and type, e.g. for tests and scenarios
+pub fn test_plane(callsign: &str, lat: f64, lng: f64, alt: f64, hdg: f64) -> Airplane {
+    Airplane::new(callsign, TEST_PLANE_TYPE, lat, lng, alt, TEST_PLANE_SPEED_KN, hdg)
+}
+
+/// Turn rate of a standard rate (rate one) turn in degrees per second
+const STANDARD_RATE_DEG_PER_S: f64 = 3.0;
+
+/// Fly a standard rate turn towards `target_heading` for `elapsed_s` seconds
+///
+/// The plane turns at 3°/s along the shortest arc and follows the curved path; once the target
+/// heading is reached it continues straight for the rest of the time.
+pub fn standard_rate_turn(plane: &Airplane, target_heading: f64, elapsed_s: f64) -> Airplane {
+    let turn_deg = (target_heading - plane.heading + 180.0).rem_euclid(360.0) - 180.0;
+    let turn_time_s = (turn_deg.abs() / STANDARD_RATE_DEG_PER_S).min(elapsed_s);
+    if turn_time_s <= 0.0 {
+        return PositionModel::default().advance(plane, elapsed_s);
+    }
+
+    // Integrate the velocity over the arc: heading(t) = start + rate * t
+    let rate = STANDARD_RATE_DEG_PER_S.copysign(turn_deg).to_radians();
+    let start = plane.heading.to_radians();
+    let end = start + rate * turn_time_s;
+    let speed_nm_per_s = plane.speed / 3600.0;
+    let north_nm = speed_nm_per_s / rate * (end.sin() - start.sin());
+    let east_nm = speed_nm_per_s / rate * (start.cos() - end.cos());
+
+    let mut turned = plane.clone();
+    (turned.latitude, turned.longitude) =
+        offset_position(plane.latitude, plane.longitude, north_nm, east_nm);
+    turned.heading = if turn_time_s * STANDARD_RATE_DEG_PER_S >= turn_deg.abs() {
+        target_heading.rem_euclid(360.0)
+    } else {
+        end.to_degrees().rem_euclid(360.0)
+    };
+    reflect_over_pole(&mut turned);
+    turned.last_update_s = plane.last_update_s + turn_time_s;
+    PositionModel::default().advance(&turned, elapsed_s - turn_time_s)
+}
+
+/// Extra load each conflict pair adds to [`capacity_utilization`], in planes
+const CONFLICT_CAPACITY_WEIGHT: f64 = 1.0;
+
+/// Share of the airspace capacity in use, between 0 and 1
+///
+/// Every plane counts once and every alerting pair adds one more plane's worth of controller
+/// workload. A capacity of zero is always fully utilized.
+pub fn capacity_utilization(planes: &[Airplane], max_capacity: usize) -> f64 {
+    if max_capacity == 0 {
+        return 1.0;
+    }
+    let conflicts = check_all_alerts(planes).len() as f64;
+    let load = planes.len() as f64 + CONFLICT_CAPACITY_WEIGHT * conflicts;
+    (load / max_capacity as f64).clamp(0.0, 1.0)
+}
+
+/// Export planes as FlightRadar24-style rows keyed by callsign
+///
+/// Each row is `[lat, lng, heading, alt, speed, squawk, "", type, callsign]`. Squawk codes are
+/// not tracked, so that column is always an empty string.
+pub fn to_fr24_rows(planes: &[Airplane]) -> serde_json::Value {
+    let rows = planes
+        .iter()
+        .map(|plane| {
+            let row = serde_json::json!([
+                plane.latitude,
+                plane.longitude,
+                plane.heading,
+                plane.altitude,
+                plane.speed,
+                "",
+                "",
+                plane.aircraft_type,
+                plane.callsign,
+            ]);
+            (plane.callsign.clone(), row)
+        })
+        .collect();
+    serde_json::Value::Object(rows)
+}
+
+/// Create a random plane on the airspace boundary around the default center, flying inbound
+fn spawn_at_boundary(rng: &mut impl Rng, used: &mut HashSet<String>, id: u64) -> Airplane {
+    let mut plane = random_airplane(rng, used, id, &GeneratorConfig::default());
+    let bearing = rng.random_range(0.0..360.0_f64);
+    (plane.latitude, plane.longitude) =
+        offset_position_along(LNZ_LAT, LNZ_LNG, bearing, AIRSPACE_RADIUS_KM / KM_PER_NM);
+    plane.heading = ((bearing + 180.0) % 360.0).round() % 360.0;
+    plane
+}
+
+/// Simulate `steps` position updates while new planes enter the airspace
+///
+/// Each step spawns `spawn_rate_per_step` planes on average (the integer part every step, the
+/// fractional part with matching probability) on the airspace boundary, heading towards the
+/// airport. Returns the planes after every step; the result is deterministic for a given seed.
+pub fn simulate_with_spawns(
+    initial: Vec<Airplane>,
+    step_s: f64,
+    steps: usize,
+    spawn_rate_per_step: f64,
+    seed: u64,
+) -> Vec<Vec<Airplane>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut used: HashSet<String> = initial.iter().map(|p| p.callsign.clone()).collect();
+    let mut next_id = initial.iter().map(|p| p.id).max().unwrap_or(0) + 1;
+    let rate = spawn_rate_per_step.max(0.0);
+    let mut planes = initial;
+    let mut snapshots = Vec::with_capacity(steps);
+    for _ in 0..steps {
+        planes = calculate_airplane_positions(&planes, step_s);
+        let mut spawns = rate.trunc() as usize;
+        if rng.random::<f64>() < rate.fract() {
+            spawns += 1;
+        }
+        for _ in 0..spawns {
+            planes.push(spawn_at_boundary(&mut rng, &mut used, next_id));
+            next_id += 1;
+        }
+        snapshots.push(planes.clone());
+    }
+    snapshots
+}
+
+/// Keep only the planes within `radius_nm` (inclusive) of `center`
+pub fn prune_outside(planes: &[Airplane], center: (f64, f64), radius_nm: f64) -> Vec<Airplane> {
+    planes
+        .iter()
+        .filter(|plane| {
+            haversine_distance_nm(center.0, center.1, plane.latitude, plane.longitude) <= radius_nm
+        })
+        .cloned()
+        .collect()
+}
+
+/// Interpolate between planes of two snapshots matched by `key`
+///
+/// Planes missing from either snapshot are dropped. Headings follow the shortest arc; all
+/// other fields are taken from the snapshot closer to `t`.
+fn interpolate_matched<K: std::hash::Hash + Eq>(
+    a: &[Airplane],
+    b: &[Airplane],
+    t: f64,
+    key: impl Fn(&Airplane) -> K,
+) -> Vec<Airplane> {
+    let later: HashMap<K, &Airplane> = b.iter().map(|plane| (key(plane), plane)).collect();
+    let lerp = |from: f64, to: f64| from + (to - from) * t;
+    a.iter()
+        .filter_map(|from| {
+            let to = later.get(&key(from))?;
+            let mut plane = if t < 0.5 { from.clone() } else { (*to).clone() };
+            plane.latitude = lerp(from.latitude, to.latitude);
+            plane.longitude = lerp(from.longitude, to.longitude);
+            plane.altitude = lerp(from.altitude, to.altitude);
+            plane.speed = lerp(from.speed, to.speed);
+            let turn = (to.heading - from.heading + 180.0).rem_euclid(360.0) - 180.0;
+            plane.heading = (from.heading + turn * t).rem_euclid(360.0);
+            Some(plane)
+        })
+        .collect()
+}
+
+/// Interpolate between two snapshots at `t` (0 = `a`, 1 = `b`), matching planes by callsign
+pub fn interpolate_snapshots(a: &[Airplane], b: &[Airplane], t: f64) -> Vec<Airplane> {
+    interpolate_matched(a, b, t, |plane| plane.callsign.clone())
+}
+
+/// Like [`interpolate_snapshots`], but matches planes by [`id`](Airplane::id)
+///
+/// Use this when callsigns may change between snapshots. If either snapshot contains an
+/// unassigned (0) or duplicate id, the ids cannot identify planes and callsigns are used instead.
+pub fn interpolate_snapshots_by_id(a: &[Airplane], b: &[Airplane], t: f64) -> Vec<Airplane> {
+    let unique_ids = |planes: &[Airplane]| {
+        let mut seen = HashSet::new();
+        planes.iter().all(|plane| plane.id != 0 && seen.insert(plane.id))
+    };
+    if unique_ids(a) && unique_ids(b) {
+        interpolate_matched(a, b, t, |plane| plane.id)
+    } else {
+        interpolate_snapshots(a, b, t)
+    }
+}
+
+/// All planes with their distance from the given point in nautical miles, nearest first
+pub fn planes_by_distance_from(planes: &[Airplane], lat: f64, lng: f64) -> Vec<(&Airplane, f64)> {
+    let mut by_distance: Vec<(&Airplane, f64)> = planes
+        .iter()
+        .map(|plane| (plane, haversine_distance_nm(lat, lng, plane.latitude, plane.longitude)))
+        .collect();
+    by_distance.sort_by(|a, b| a.1.total_cmp(&b.1));
+    by_distance
+}
+
+/// Count alerts over a replay per grid cell of `cell_deg` degrees
+///
+/// Each alert is located at the midpoint between the two planes. Cell `(row, col)` covers
+/// latitudes from `row * cell_deg` and longitudes from `col * cell_deg` (both rounded down),
+/// so cells are stable across snapshots and replays.
+pub fn conflict_hotspots(
+    snapshots: &[Vec<Airplane>],
+    cell_deg: f64,
+) -> HashMap<(i64, i64), usize> {
+    let mut counts = HashMap::new();
+    for planes in snapshots {
+        for (i, j, _) in check_all_alerts_indexed(planes) {
+            let (lat, lng) = great_circle_midpoint(
+                (planes[i].latitude, planes[i].longitude),
+                (planes[j].latitude, planes[j].longitude),
+            );
+            let cell = ((lat / cell_deg).floor() as i64, (lng / cell_deg).floor() as i64);
+            *counts.entry(cell).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// Angle at which planes are vectored onto the extended runway centerline (degrees)
+const LOCALIZER_INTERCEPT_DEG: f64 = 30.0;
+/// Cross-track distance within which a plane counts as established on the centerline (nm)
+const LOCALIZER_CAPTURE_NM: f64 = 0.1;
+
+/// Heading that intercepts the extended centerline of a runway at a 30° angle
+///
+/// `final_course_deg` is the inbound course towards `runway_threshold`. Planes within 0.1nm of
+/// the centerline are established and get the final course itself.
+pub fn intercept_localizer(
+    plane: &Airplane,
+    runway_threshold: (f64, f64),
+    final_course_deg: f64,
+) -> f64 {
+    let (north, east) = local_offset_nm(
+        runway_threshold.0,
+        runway_threshold.1,
+        plane.latitude,
+        plane.longitude,
+    );
+    let course = final_course_deg.to_radians();
+    // Positive when the plane is right of the centerline (seen along the final course)
+    let cross_track_nm = east * course.cos() - north * course.sin();
+    let heading = if cross_track_nm > LOCALIZER_CAPTURE_NM {
+        final_course_deg - LOCALIZER_INTERCEPT_DEG
+    } else if cross_track_nm < -LOCALIZER_CAPTURE_NM {
+        final_course_deg + LOCALIZER_INTERCEPT_DEG
+    } else {
+        final_course_deg
+    };
+    heading.rem_euclid(360.0)
+}
+
+/// Plane with the age of its position report, as written by [`snapshot_to_json_with_age`]
+#[derive(Serialize)]
+struct PlaneWithAge<'a> {
+    #[serde(flatten)]
+    plane: &'a Airplane,
+    age_s: f64,
+}
+
+/// Serialize planes as JSON objects with an additional `age_s` field relative to `now_s`
+pub fn snapshot_to_json_with_age(planes: &[Airplane], now_s: f64) -> String {
+    let planes: Vec<PlaneWithAge> =
+        planes.iter().map(|plane| PlaneWithAge { plane, age_s: plane.age_s(now_s) }).collect();
+    serde_json::to_string(&planes).expect("planes are always serializable")
+}
+
+/// Altitude below which a plane inside a runway polygon counts as occupying the runway (feet)
+const RUNWAY_OCCUPANCY_ALTITUDE_FT: f64 = 500.0;
+
+/// Callsigns of planes occupying the runway while at least one other plane does as well
+///
+/// A plane occupies the runway if it is inside `runway_polygon` (lat, lng vertices) below
+/// 500ft. Returns an empty list if at most one plane occupies it.
+pub fn runway_incursions(planes: &[Airplane], runway_polygon: &[(f64, f64)]) -> Vec<String> {
+    let occupying: Vec<String> = planes
+        .iter()
+        .filter(|plane| {
+            plane.altitude < RUNWAY_OCCUPANCY_ALTITUDE_FT
+                && point_in_polygon(plane.latitude, plane.longitude, runway_polygon)
+        })
+        .map(|plane| plane.callsign.clone())
+        .collect();
+    if occupying.len() > 1 { occupying } else { Vec::new() }
+}
+
+/// Distances in nautical miles from one point to every plane, in input order
+///
+/// Same results as calling [`haversine_distance_nm`] per plane, but the cosine of the query
+/// latitude is computed only once.
+pub fn distances_from(lat: f64, lng: f64, planes: &[Airplane]) -> Vec<f64> {
+    let cos_lat = cos_deg(lat);
+    planes
+        .iter()
+        .map(|plane| {
+            haversine_distance_with_cos_nm(lat, lng, cos_lat, plane.latitude, plane.longitude)
+        })
+        .collect()
+}
+
+/// Shuffle planes in place; the resulting order is deterministic for a given seed
+pub fn shuffle_planes(planes: &mut [Airplane], seed: u64) {
+    planes.shuffle(&mut StdRng::seed_from_u64(seed));
+}
+
+/// Wind as reported in METARs: the direction it blows from and its speed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Wind {
+    /// Direction the wind is coming from in degrees
+    pub from_deg: f64,
+    pub speed_kn: f64,
+}
+
+/// Heading to fly so that the ground track follows `desired_track_deg` (wind triangle)
+///
+/// Returns `None` if the wind is faster than the true airspeed, in which case the track
+/// cannot be maintained.
+pub fn wind_correction_heading(desired_track_deg: f64, tas_kn: f64, wind: &Wind) -> Option<f64> {
+    if tas_kn <= 0.0 || wind.speed_kn > tas_kn {
+        return None;
+    }
+    let wind_angle = (wind.from_deg - desired_track_deg).to_radians();
+    let correction = (wind.speed_kn * wind_angle.sin() / tas_kn).asin().to_degrees();
+    Some((desired_track_deg + correction).rem_euclid(360.0))
+}
+
+/// Bearings in degrees from `center` to every plane involved in at least one alert
+///
+/// Each plane appears once, in input order.
+pub fn alert_bearing_distribution(center: (f64, f64), planes: &[Airplane]) -> Vec<f64> {
+    let mut involved = vec![false; planes.len()];
+    for (i, j, _) in check_all_alerts_indexed(planes) {
+        involved[i] = true;
+        involved[j] = true;
+    }
+    planes
+        .iter()
+        .zip(involved)
+        .filter(|(_, involved)| *involved)
+        .map(|(plane, _)| initial_bearing_deg(center.0, center.1, plane.latitude, plane.longitude))
+        .collect()
+}
+
+/// Planes inside the bounding box `(min_lat, min_lng, max_lat, max_lng)` (edges included)
+pub fn planes_in_bbox(planes: &[Airplane], bbox: (f64, f64, f64, f64)) -> Vec<Airplane> {
+    let (min_lat, min_lng, max_lat, max_lng) = bbox;
+    planes
+        .iter()
+        .filter(|plane| {
+            (min_lat..=max_lat).contains(&plane.latitude)
+                && (min_lng..=max_lng).contains(&plane.longitude)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Snapshot of the visible part of the traffic, as written by [`viewport_snapshot_json`]
+#[derive(Serialize)]
+struct ViewportSnapshot {
+    time_s: f64,
+    planes: Vec<Airplane>,
+    alerts: Vec<Alert>,
+}
+
+/// Serialize the planes inside `bbox` (see [`planes_in_bbox`]) together with their alerts
+///
+/// Produces `{"time_s": .., "planes": [..], "alerts": [..]}`. Alerts are only computed among
+/// the visible planes, so conflicts with planes outside the viewport are not included.
+pub fn viewport_snapshot_json(
+    planes: &[Airplane],
+    bbox: (f64, f64, f64, f64),
+    now_s: f64,
+) -> String {
+    let planes = planes_in_bbox(planes, bbox);
+    let alerts = check_all_alerts(&planes);
+    let snapshot = ViewportSnapshot { time_s: now_s, planes, alerts };
+    serde_json::to_string(&snapshot).expect("viewport snapshot is always serializable")
+}
+
+/// Smallest horizontal distance between two planes over the next `horizon_s` seconds
+///
+/// Sampled every [`LOOK_AHEAD_STEP_S`] seconds, including now and the end of the horizon.
+/// `horizon_s` must be finite and not negative.
+fn min_separation_within_nm(plane1: &Airplane, plane2: &Airplane, horizon_s: f64) -> f64 {
+    let steps = (horizon_s / LOOK_AHEAD_STEP_S).ceil().max(0.0) as usize;
+    let model = PositionModel::default();
+    (0..=steps)
+        .map(|step| {
+            let elapsed = (step as f64 * LOOK_AHEAD_STEP_S).min(horizon_s);
+            horizontal_distance_nm(&model.advance(plane1, elapsed), &model.advance(plane2, elapsed))
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// Smallest heading change (degrees, positive = right) keeping the ownship clear of the intruder
+///
+/// The ownship is assumed to turn immediately while the intruder keeps its track; both keep
+/// their speed. Turns are tried in 1° increments, right before left for equal angles. Returns
+/// `None` if the ownship stays outside `protected_nm` over the horizon without turning, if no
+/// turn up to 180° is sufficient (e.g. the intruder is already inside the protected radius),
+/// or unless `horizon_s` is finite and not negative.
+pub fn avoidance_turn_deg(
+    ownship: &Airplane,
+    intruder: &Airplane,
+    protected_nm: f64,
+    horizon_s: f64,
+) -> Option<f64> {
+    if !horizon_s.is_finite() || horizon_s < 0.0 {
+        return None;
+    }
+    if min_separation_within_nm(ownship, intruder, horizon_s) >= protected_nm {
+        return None;
+    }
+    (1..=180)
+        .flat_map(|angle| [angle as f64, -(angle as f64)])
+        .find(|turn| {
+            let turned = Airplane {
+                heading: (ownship.heading + turn).rem_euclid(360.0),
+                ..ownship.clone()
+            };
+            min_separation_within_nm(&turned, intruder, horizon_s) >= protected_nm
+        })
+}
+
+/// Callsigns of the fixed planes placed at the start of every generated fleet
+const TEST_PLANE_CALLSIGNS: [&str; 2] = ["TEST001", "TEST002"];
+
+/// Check a snapshot for internal consistency and describe every problem found
+///
+/// Checks that every plane passes [`Airplane::validate`], that callsigns are unique and that
+/// the fixed TEST001/TEST002 pair is complete: a snapshot containing one of them must contain
+/// the other as well.
+pub fn validate_snapshot(planes: &[Airplane]) -> Result<(), Vec<String>> {
+    let mut problems: Vec<String> = validate_all(planes)
+        .into_iter()
+        .map(|(index, err)| format!("plane {index} ({}): {err}", planes[index].callsign))
+        .collect();
+
+    let mut seen = HashSet::new();
+    for (index, plane) in planes.iter().enumerate() {
+        if !seen.insert(plane.callsign.as_str()) {
+            problems.push(format!("plane {index}: duplicate callsign {}", plane.callsign));
+        }
+    }
+
+    let present = TEST_PLANE_CALLSIGNS.map(|callsign| seen.contains(callsign));
+    if present.contains(&true) {
+        for (callsign, present) in TEST_PLANE_CALLSIGNS.iter().zip(present) {
+            if !present {
+                problems.push(format!("test plane {callsign} is missing"));
+            }
+        }
+    }
+
+    if problems.is_empty() { Ok(()) } else { Err(problems) }
+}
+
+/// Placements tried per plane by [`generate_conflict_free`] before giving up
+const CONFLICT_FREE_MAX_ATTEMPTS: usize = 100;
+
+/// Generate up to `n` random planes (without the test planes) of which no pair triggers an alert
+///
+/// Conflicting placements are rejected and redrawn. The demo airspace only fits a limited
+/// number of separated planes: once a plane cannot be placed within
+/// [`CONFLICT_FREE_MAX_ATTEMPTS`] draws, generation stops and fewer than `n` planes are
+/// returned. The result is deterministic for a given seed.
+pub fn generate_conflict_free(n: usize, seed: u64) -> Vec<Airplane> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let config = GeneratorConfig::default();
+    let mut used = HashSet::new();
+    let mut planes = Vec::with_capacity(n);
+    while planes.len() < n {
+        let placed = (0..CONFLICT_FREE_MAX_ATTEMPTS).find_map(|_| {
+            let candidate = random_airplane(&mut rng, &mut used, planes.len() as u64 + 1, &config);
+            if planes.iter().all(|p| check_alert_between_planes(p, &candidate).is_none()) {
+                Some(candidate)
+            } else {
+                used.remove(&candidate.callsign);
+                None
+            }
+        });
+        match placed {
+            Some(plane) => planes.push(plane),
+            None => break,
+        }
+    }
+    planes
+}
+
+/// Whether two planes are getting closer, moving apart or keeping their distance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum SeparationTrend {
+    Converging,
+    Diverging,
+    /// Range rate below 1kn in either direction
+    Parallel,
+}
+
+/// Range rate below which a pair counts as keeping its distance (knots)
+const PARALLEL_RANGE_RATE_KN: f64 = 1.0;
+
+/// Classify the current change in horizontal distance between two planes
+pub fn separation_trend(plane1: &Airplane, plane2: &Airplane) -> SeparationTrend {
+    let closure = closure_rate_kn(plane1, plane2);
+    if closure >= PARALLEL_RANGE_RATE_KN {
+        SeparationTrend::Converging
+    } else if closure <= -PARALLEL_RANGE_RATE_KN {
+        SeparationTrend::Diverging
+    } else {
+        SeparationTrend::Parallel
+    }
+}
+
+/// Planes carrying the given tag, in input order
+pub fn planes_with_tag<'a>(planes: &'a [Airplane], tag: &str) -> Vec<&'a Airplane> {
+    planes.iter().filter(|plane| plane.tags.iter().any(|t| t == tag)).collect()
+}
+
+/// Average velocity of all planes as (north, east) components in knots; `(0, 0)` if empty
+pub fn mean_velocity_kn(planes: &[Airplane]) -> (f64, f64) {
+    if planes.is_empty() {
+        return (0.0, 0.0);
+    }
+    let (north, east) = planes.iter().map(velocity_components_kn).fold((0.0, 0.0), |sum, v| {
+        (sum.0 + v.0, sum.1 + v.1)
+    });
+    let count = planes.len() as f64;
+    (north / count, east / count)
+}
+
+/// Pairs at the same level (within `alt_tol_ft`) that are horizontally converging
+///
+/// Unlike [`check_all_alerts`] this ignores the horizontal distance, so it warns about
+/// co-level traffic long before it enters the alert box.
+pub fn level_conflicts(planes: &[Airplane], alt_tol_ft: f64) -> Vec<(String, String)> {
+    let mut conflicts = Vec::new();
+    for (i, plane1) in planes.iter().enumerate() {
+        for plane2 in &planes[i + 1..] {
+            if altitude_diff_ft(plane1, plane2) <= alt_tol_ft
+                && separation_trend(plane1, plane2) == SeparationTrend::Converging
+            {
+                conflicts.push((plane1.callsign.clone(), plane2.callsign.clone()));
+            }
+        }
+    }
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_haversine_distance() {
+        let distance = haversine_distance_nm(48.250000, 14.191473, 48.265000, 14.191473);
+        assert!((distance - 0.9).abs() < 0.01, "distance was {distance}");
+    }
+
+    #[test]
+    fn test_demo_airplanes_contain_test_planes() {
+        let planes = generate_demo_airplanes();
+        assert_eq!(planes.len(), NUM_DEMO_PLANES);
+        assert!(planes.iter().any(|p| p.callsign == "TEST001"));
+        assert!(planes.iter().any(|p| p.callsign == "TEST002"));
+
+        let callsigns: HashSet<_> = planes.iter().map(|p| &p.callsign).collect();
+        assert_eq!(callsigns.len(), planes.len());
+        assert!(validate_all(&planes).is_empty());
+    }
+
+    #[test]
+    fn test_calculate_positions_northbound() {
+        let plane = Airplane::new("N1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 120.0, 0.0);
+        let updated = calculate_airplane_positions(&[plane], 3600.0);
+        assert!((updated[0].latitude - (LNZ_LAT + 2.0)).abs() < 1e-9);
+        assert!((updated[0].longitude - LNZ_LNG).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_test_planes_trigger_alert() {
+        // The test planes start ~6nm apart and close in at 240kn
+        let planes = calculate_airplane_positions(&generate_demo_airplanes(), 60.0);
+        let alerts = check_all_alerts(&planes);
+        assert!(alerts.iter().any(|a| {
+            (a.plane1_callsign == "TEST001" && a.plane2_callsign == "TEST002")
+                || (a.plane1_callsign == "TEST002" && a.plane2_callsign == "TEST001")
+        }));
+    }
+
+    #[test]
+    fn test_validate_all_collects_errors() {
+        let planes = vec![
+            Airplane::new("OK1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 250.0, 90.0),
+            Airplane::new("BAD1", "A320", 95.0, LNZ_LNG, 30000.0, 250.0, 90.0),
+            Airplane::new("OK2", "B738", LNZ_LAT, LNZ_LNG, 25000.0, 300.0, 180.0),
+            Airplane::new("BAD2", "B738", LNZ_LAT, LNZ_LNG, 25000.0, -10.0, 180.0),
+        ];
+
+        let errors = validate_all(&planes);
+        assert_eq!(
+            errors,
+            vec![
+                (1, AirplaneError::InvalidLatitude(95.0)),
+                (3, AirplaneError::InvalidSpeed(-10.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_traffic_convex_hull_square() {
+        let planes = vec![
+            Airplane::new("SW", "A320", 48.0, 14.0, 30000.0, 250.0, 0.0),
+            Airplane::new("SE", "A320", 48.0, 15.0, 30000.0, 250.0, 0.0),
+            Airplane::new("NE", "A320", 49.0, 15.0, 30000.0, 250.0, 0.0),
+            Airplane::new("NW", "A320", 49.0, 14.0, 30000.0, 250.0, 0.0),
+            Airplane::new("MID", "A320", 48.5, 14.5, 30000.0, 250.0, 0.0),
+        ];
+
+        let mut hull = traffic_convex_hull(&planes);
+        hull.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+        assert_eq!(hull, vec![(48.0, 14.0), (48.0, 15.0), (49.0, 14.0), (49.0, 15.0)]);
+    }
+
+    #[test]
+    fn test_traffic_convex_hull_few_planes() {
+        let planes = vec![Airplane::new("A1", "A320", 48.0, 14.0, 30000.0, 250.0, 0.0)];
+        assert_eq!(traffic_convex_hull(&planes), vec![(48.0, 14.0)]);
+        assert!(traffic_convex_hull(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_traffic_convex_hull_co_located_planes() {
+        let planes: Vec<_> = (0..4)
+            .map(|i| Airplane::new(&format!("C{i}"), "A320", 48.0, 14.0, 30000.0, 250.0, 0.0))
+            .collect();
+        assert_eq!(traffic_convex_hull(&planes), vec![(48.0, 14.0)]);
+
+        // Two distinct positions shared by several planes
+        let mut planes = planes;
+        planes[3].longitude = 15.0;
+        planes[2].longitude = 15.0;
+        assert_eq!(traffic_convex_hull(&planes), vec![(48.0, 14.0), (48.0, 15.0)]);
+    }
+
+    #[test]
+    fn test_altitude_diff_ft() {
+        let plane1 = Airplane::new("A1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 250.0, 0.0);
+        let plane2 = Airplane::new("A2", "A320", LNZ_LAT, LNZ_LNG, 29500.0, 250.0, 0.0);
+        assert_eq!(altitude_diff_ft(&plane1, &plane2), 500.0);
+        assert_eq!(altitude_diff_ft(&plane2, &plane1), 500.0);
+    }
+
+    #[test]
+    fn test_add_position_noise() {
+        let planes = generate_demo_airplanes();
+        let noisy = add_position_noise(&planes, 0.5, 100.0, 42);
+
+        for (original, perturbed) in planes.iter().zip(&noisy) {
+            let offset_nm = haversine_distance_nm(
+                original.latitude,
+                original.longitude,
+                perturbed.latitude,
+                perturbed.longitude,
+            );
+            assert!(offset_nm < 5.0 * 0.5, "offset was {offset_nm}nm");
+            assert!(altitude_diff_ft(original, perturbed) < 5.0 * 100.0);
+        }
+
+        let again = add_position_noise(&planes, 0.5, 100.0, 42);
+        for (a, b) in noisy.iter().zip(&again) {
+            assert_eq!((a.latitude, a.longitude, a.altitude), (b.latitude, b.longitude, b.altitude));
+        }
+
+        let other_seed = add_position_noise(&planes, 0.5, 100.0, 43);
+        assert!(noisy.iter().zip(&other_seed).any(|(a, b)| a.latitude != b.latitude));
+    }
+
+    #[test]
+    fn test_reciprocal_traffic() {
+        let mut planes = generate_demo_airplanes();
+        planes.truncate(2);
+        planes.push(Airplane::new("OTHER1", "A320", LNZ_LAT, LNZ_LNG, 29800.0, 250.0, 90.0));
+        planes.push(Airplane::new("OTHER2", "A320", LNZ_LAT, LNZ_LNG, 20000.0, 250.0, 0.0));
+
+        let pairs = reciprocal_traffic(&planes, 5.0, 1000.0);
+        assert_eq!(pairs, vec![("TEST001".to_string(), "TEST002".to_string())]);
+    }
+
+    #[test]
+    fn test_detect_handoffs() {
+        let previous = vec![
+            Airplane::new("STAY1", "A320", 48.0, 14.0, 30000.0, 250.0, 0.0),
+            Airplane::new("OLD1", "A320", 48.5, 14.5, 30000.0, 250.0, 90.0),
+        ];
+        let current = vec![
+            Airplane::new("STAY1", "A320", 48.01, 14.0, 30000.0, 250.0, 0.0),
+            Airplane::new("NEW1", "A320", 48.5, 14.51, 30000.0, 250.0, 90.0),
+            Airplane::new("FAR1", "A320", 47.0, 13.0, 30000.0, 250.0, 90.0),
+        ];
+
+        let handoffs = detect_handoffs(&previous, &current, 2.0);
+        assert_eq!(handoffs, vec![("OLD1".to_string(), "NEW1".to_string())]);
+
+        assert!(detect_handoffs(&previous, &current, 0.1).is_empty());
+    }
+
+    #[test]
+    fn test_alerts_csv_round_trip() {
+        let planes = calculate_airplane_positions(&generate_demo_airplanes(), 60.0);
+        let alerts = check_all_alerts(&planes);
+        assert!(!alerts.is_empty());
+
+        let csv = alerts_to_csv(&alerts);
+        assert!(csv.starts_with(ALERT_CSV_HEADER));
+        assert_eq!(alerts_from_csv(&csv).unwrap(), alerts);
+
+        let csv = format!("{ALERT_CSV_HEADER}\nA,B,x,1,Warning,HeadOn,false\n");
+        let err = alerts_from_csv(&csv).unwrap_err();
+        assert_eq!(err.line, 2);
+    }
+
+    #[test]
+    fn test_check_all_alerts_with_custom_metric() {
+        let planes = vec![
+            Airplane::new("FAR1", "A320", 48.0, 14.0, 30000.0, 250.0, 0.0),
+            Airplane::new("FAR2", "A320", 49.0, 15.0, 30500.0, 250.0, 0.0),
+        ];
+        assert!(check_all_alerts(&planes).is_empty());
+
+        // Metric that ignores the horizontal position entirely
+        let alerts = check_all_alerts_with(&planes, &AlertConfig::default(), |a, b| {
+            altitude_diff_ft(a, b) / 1000.0
+        });
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].distance_nm, 0.5);
+    }
+
+    #[test]
+    fn test_is_daytime_over_lnz() {
+        let plane = Airplane::new("DAY1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 250.0, 0.0);
+        // 2025-06-21 12:00:00 UTC
+        assert!(plane.is_daytime(1_750_507_200));
+        // 2025-06-21 00:00:00 UTC
+        assert!(!plane.is_daytime(1_750_464_000));
+        // Sunrise is around 03:02 UTC
+        assert!(!plane.is_daytime(1_750_474_500)); // 02:55 UTC
+        assert!(plane.is_daytime(1_750_475_100)); // 03:05 UTC
+    }
+
+    #[test]
+    fn test_wake_category() {
+        assert_eq!(wake_category("A388"), WakeCategory::Super);
+        assert_eq!(wake_category("B77W"), WakeCategory::Heavy);
+        assert_eq!(wake_category("A320"), WakeCategory::Medium);
+        assert_eq!(wake_category("C172"), WakeCategory::Light);
+        assert_eq!(wake_category("XXXX"), WakeCategory::Medium);
+    }
+
+    #[test]
+    fn test_wake_separation_alerts() {
+        // Light plane 5.5nm behind a northbound heavy, required spacing is 6nm
+        let heavy = Airplane::new("HVY1", "B77W", LNZ_LAT, LNZ_LNG, 5000.0, 160.0, 0.0);
+        let light = Airplane::new("LGT1", "C172", LNZ_LAT - 5.5 / 60.0, LNZ_LNG, 4800.0, 100.0, 0.0);
+        let alerts = wake_separation_alerts(&[heavy.clone(), light.clone()]);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].plane1_callsign, "HVY1");
+        assert_eq!(alerts[0].plane2_callsign, "LGT1");
+
+        // Light plane ahead of the heavy is not affected by its wake
+        let ahead = Airplane::new("LGT2", "C172", LNZ_LAT + 5.5 / 60.0, LNZ_LNG, 4800.0, 100.0, 0.0);
+        assert!(wake_separation_alerts(&[heavy.clone(), ahead]).is_empty());
+
+        // Sufficient spacing behind the heavy
+        let spaced = Airplane::new("LGT3", "C172", LNZ_LAT - 7.0 / 60.0, LNZ_LNG, 4800.0, 100.0, 0.0);
+        assert!(wake_separation_alerts(&[heavy.clone(), spaced]).is_empty());
+
+        // Same category in trail is not a wake conflict
+        let other_heavy =
+            Airplane::new("HVY2", "B789", LNZ_LAT - 3.0 / 60.0, LNZ_LNG, 4800.0, 160.0, 0.0);
+        assert!(wake_separation_alerts(&[heavy, other_heavy]).is_empty());
+    }
+
+    #[test]
+    fn test_wake_separation_alerts_single_plane() {
+        let heavy = Airplane::new("HVY1", "B77W", LNZ_LAT, LNZ_LNG, 5000.0, 160.0, 180.0);
+        assert!(wake_separation_alerts(&[heavy]).is_empty());
+    }
+
+    #[test]
+    fn test_generate_unique_fleet() {
+        let fleet = generate_unique_fleet(500, 7);
+        assert_eq!(fleet.len(), 500);
+        let callsigns: HashSet<_> = fleet.iter().map(|p| &p.callsign).collect();
+        assert_eq!(callsigns.len(), 500);
+
+        let again = generate_unique_fleet(500, 7);
+        assert!(fleet.iter().zip(&again).all(|(a, b)| a.callsign == b.callsign));
+    }
+
+    #[test]
+    fn test_relative_velocity_head_on() {
+        let northbound = Airplane::new("N1", "A320", 48.0, 14.0, 30000.0, 120.0, 0.0);
+        let southbound = Airplane::new("S1", "A320", 48.1, 14.0, 30000.0, 120.0, 180.0);
+
+        let (north, east) = relative_velocity_kn(&northbound, &southbound);
+        assert!((north + 240.0).abs() < 1e-9);
+        assert!(east.abs() < 1e-9);
+
+        let (north, east) = relative_velocity_kn(&southbound, &northbound);
+        assert!((north - 240.0).abs() < 1e-9);
+        assert!(east.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_crossing_traffic_vertical_separation() {
+        // Eastbound and northbound planes crossing at right angles, 0.5nm apart
+        let eastbound = Airplane::new("E1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 250.0, 90.0);
+        let offset_lat = LNZ_LAT + 0.5 / 60.0;
+
+        let separated = Airplane::new("N1", "A320", offset_lat, LNZ_LNG, 32000.0, 250.0, 0.0);
+        assert!(check_alert_between_planes(&eastbound, &separated).is_none());
+
+        let conflicting = Airplane::new("N2", "A320", offset_lat, LNZ_LNG, 30500.0, 250.0, 0.0);
+        let alert = check_alert_between_planes(&eastbound, &conflicting).unwrap();
+        assert!((alert.distance_nm - 0.5).abs() < 0.01);
+        assert_eq!(alert.altitude_diff_ft, 500.0);
+    }
+
+    #[test]
+    fn test_alert_threshold_boundaries() {
+        assert!(!is_loss_of_separation(5.0, 0.0));
+        assert!(is_loss_of_separation(4.999, 0.0));
+        assert!(!is_loss_of_separation(0.0, 1000.0));
+        assert!(is_loss_of_separation(0.0, 999.9));
+
+        // Exactly 1000ft apart at the same position
+        let plane1 = Airplane::new("A1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 250.0, 0.0);
+        let plane2 = Airplane::new("A2", "A320", LNZ_LAT, LNZ_LNG, 29000.0, 250.0, 0.0);
+        assert!(check_alert_between_planes(&plane1, &plane2).is_none());
+
+        // Exactly 5nm apart according to the distance metric
+        let alerts = check_all_alerts_with(&[plane1.clone(), plane1], &AlertConfig::default(), |_, _| 5.0);
+        assert!(alerts.is_empty());
+    }
+
+    #[test]
+    fn test_predict_trajectory_northbound() {
+        let plane = Airplane::new("N1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 250.0, 0.0);
+        let trajectory = predict_trajectory(&plane, 10.0, 6);
+
+        assert_eq!(trajectory.len(), 6);
+        assert!(trajectory[0].0 > LNZ_LAT);
+        assert!(trajectory.windows(2).all(|w| w[1].0 > w[0].0));
+        assert!(trajectory.iter().all(|(_, lng)| (lng - LNZ_LNG).abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_calculate_positions_with_hook() {
+        let planes = generate_demo_airplanes();
+        let updated = calculate_airplane_positions_with(&planes, 10.0, |plane, _| {
+            plane.heading = (plane.heading + 10.0).rem_euclid(360.0);
+        });
+
+        let plain = calculate_airplane_positions(&planes, 10.0);
+        for ((original, hooked), moved) in planes.iter().zip(&updated).zip(&plain) {
+            assert!((heading_difference_deg(hooked.heading, original.heading) - 10.0).abs() < 1e-9);
+            assert_eq!((hooked.latitude, hooked.longitude), (moved.latitude, moved.longitude));
+        }
+    }
+
+    #[test]
+    fn test_severity_histogram() {
+        let planes = vec![
+            // Warning: 2nm apart
+            Airplane::new("W1", "A320", 48.0, 14.0, 30000.0, 250.0, 0.0),
+            Airplane::new("W2", "A320", 48.0 + 2.0 / 60.0, 14.0, 30000.0, 250.0, 0.0),
+            // Advisory: 4nm apart
+            Airplane::new("A1", "A320", 49.0, 14.0, 30000.0, 250.0, 0.0),
+            Airplane::new("A2", "A320", 49.0 + 4.0 / 60.0, 14.0, 30000.0, 250.0, 0.0),
+        ];
+
+        let histogram = severity_histogram(&planes);
+        assert_eq!(histogram[&AlertSeverity::Warning], 1);
+        assert_eq!(histogram[&AlertSeverity::Advisory], 1);
+        assert_eq!(histogram[&AlertSeverity::Critical], 0);
+    }
+
+    #[test]
+    fn test_speed_limit_violations() {
+        let planes = vec![
+            Airplane::new("FAST1", "A320", LNZ_LAT, LNZ_LNG, 8000.0, 300.0, 0.0),
+            Airplane::new("SLOW1", "A320", LNZ_LAT, LNZ_LNG, 8000.0, 200.0, 0.0),
+            Airplane::new("HIGH1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 450.0, 0.0),
+        ];
+
+        let violations = speed_limit_violations(&planes);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].callsign, "FAST1");
+    }
+
+    #[test]
+    fn test_round_snapshot() {
+        let planes = vec![Airplane::new("R1", "A320", 48.238575, 14.191473, 29512.64, 250.0, 0.0)];
+        let rounded = round_snapshot(&planes, 4, 0);
+        assert_eq!(rounded[0].latitude, 48.2386);
+        assert_eq!(rounded[0].longitude, 14.1915);
+        assert_eq!(rounded[0].altitude, 29513.0);
+        assert_eq!(rounded[0].callsign, "R1");
+    }
+
+    #[test]
+    fn test_traffic_centroid() {
+        assert_eq!(traffic_centroid(&[]), None);
+
+        let around_lnz = vec![
+            Airplane::new("A1", "A320", LNZ_LAT + 0.1, LNZ_LNG, 30000.0, 250.0, 0.0),
+            Airplane::new("A2", "A320", LNZ_LAT - 0.1, LNZ_LNG, 30000.0, 250.0, 0.0),
+            Airplane::new("A3", "A320", LNZ_LAT, LNZ_LNG + 0.2, 30000.0, 250.0, 0.0),
+            Airplane::new("A4", "A320", LNZ_LAT, LNZ_LNG - 0.2, 30000.0, 250.0, 0.0),
+        ];
+        let (lat, lng) = traffic_centroid(&around_lnz).unwrap();
+        assert!((lat - LNZ_LAT).abs() < 1e-9);
+        assert!((lng - LNZ_LNG).abs() < 1e-6);
+
+        let antimeridian = vec![
+            Airplane::new("E1", "A320", 10.0, 179.0, 30000.0, 250.0, 0.0),
+            Airplane::new("W1", "A320", 20.0, -179.0, 30000.0, 250.0, 0.0),
+        ];
+        let (lat, lng) = traffic_centroid(&antimeridian).unwrap();
+        assert!((lat - 15.0).abs() < 1e-9);
+        assert!((lng.abs() - 180.0).abs() < 1e-6, "longitude was {lng}");
+    }
+
+    #[test]
+    fn test_alert_graph() {
+        let planes = vec![
+            Airplane::new("HUB", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 250.0, 0.0),
+            Airplane::new("NORTH", "A320", LNZ_LAT + 3.0 / 60.0, LNZ_LNG, 30000.0, 250.0, 0.0),
+            Airplane::new("SOUTH", "A320", LNZ_LAT - 3.0 / 60.0, LNZ_LNG, 30000.0, 250.0, 0.0),
+            Airplane::new("FAR", "A320", LNZ_LAT + 1.0, LNZ_LNG, 30000.0, 250.0, 0.0),
+        ];
+
+        let mut graph = alert_graph(&planes);
+        graph.values_mut().for_each(|neighbors| neighbors.sort());
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph["HUB"], vec!["NORTH".to_string(), "SOUTH".to_string()]);
+        assert_eq!(graph["NORTH"], vec!["HUB".to_string()]);
+        assert_eq!(graph["SOUTH"], vec!["HUB".to_string()]);
+        assert!(!graph.contains_key("FAR"));
+    }
+
+    #[test]
+    fn test_sim_clock_step() {
+        let mut clock = SimClock::new(30.0);
+        let planes = vec![Airplane::new("N1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 120.0, 0.0)];
+
+        let planes = clock.step(planes);
+        let planes = clock.step(planes);
+        assert_eq!(clock.time_s, 60.0);
+        // 120kn for one minute is 2nm
+        assert!((planes[0].latitude - (LNZ_LAT + 2.0 / 60.0)).abs() < 1e-9);
+
+        clock.advance(5.0);
+        assert_eq!(clock.time_s, 65.0);
+    }
+
+    #[test]
+    fn test_alert_config_aircraft_types() {
+        let config = AlertConfig {
+            aircraft_types: Some(["B738", "B789"].iter().map(|t| t.to_string()).collect()),
+            ..AlertConfig::default()
+        };
+        let boeing = Airplane::new("BOE1", "B738", LNZ_LAT, LNZ_LNG, 30000.0, 250.0, 0.0);
+        let atr = Airplane::new("ATR1", "AT76", LNZ_LAT + 1.0 / 60.0, LNZ_LNG, 30000.0, 250.0, 0.0);
+        let other_boeing =
+            Airplane::new("BOE2", "B789", LNZ_LAT - 1.0 / 60.0, LNZ_LNG + 1.0, 30000.0, 250.0, 0.0);
+        let near_boeing =
+            Airplane::new("BOE3", "B789", LNZ_LAT - 1.0 / 60.0, LNZ_LNG, 30000.0, 250.0, 0.0);
+
+        let planes = vec![boeing.clone(), atr.clone()];
+        assert_eq!(check_all_alerts(&planes).len(), 1);
+        assert!(check_all_alerts_with(&planes, &config, horizontal_distance_nm).is_empty());
+
+        let planes = vec![boeing, atr, other_boeing, near_boeing];
+        let alerts = check_all_alerts_with(&planes, &config, horizontal_distance_nm);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].plane1_callsign, "BOE1");
+        assert_eq!(alerts[0].plane2_callsign, "BOE3");
+    }
+
+    #[test]
+    fn test_detect_turning() {
+        let previous = vec![
+            Airplane::new("TURN1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 250.0, 350.0),
+            Airplane::new("STRAIGHT1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 250.0, 90.0),
+        ];
+        // TURN1 turned 20° across north in 2s, STRAIGHT1 only 2°
+        let current = vec![
+            Airplane::new("TURN1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 250.0, 10.0),
+            Airplane::new("STRAIGHT1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 250.0, 92.0),
+            Airplane::new("NEW1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 250.0, 180.0),
+        ];
+
+        assert_eq!(detect_turning(&previous, &current, 2.0, 5.0), vec!["TURN1".to_string()]);
+
+        // Without elapsed time no turn rate can be derived
+        for dt_s in [0.0, -2.0, f64::NAN, f64::INFINITY] {
+            assert!(detect_turning(&previous, &current, dt_s, 5.0).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_new_checked() {
+        let plane = Airplane::new_checked("OK1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 250.0, 90.0);
+        assert_eq!(plane.unwrap().callsign, "OK1");
+
+        let err = Airplane::new_checked("BAD1", "A320", 200.0, LNZ_LNG, 30000.0, 250.0, 90.0);
+        assert_eq!(err.unwrap_err(), AirplaneError::InvalidLatitude(200.0));
+    }
+
+    #[test]
+    fn test_simulate_with_odometer() {
+        let planes = vec![
+            Airplane::new("SLOW1", "AT76", LNZ_LAT, LNZ_LNG, 20000.0, 120.0, 45.0),
+            Airplane::new("FAST1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 480.0, 270.0),
+        ];
+
+        let odometer = simulate_with_odometer(&planes, 60.0, 60);
+        assert!((odometer["SLOW1"] - 120.0).abs() < 0.5, "SLOW1 flew {}", odometer["SLOW1"]);
+        assert!((odometer["FAST1"] - 480.0).abs() < 2.0, "FAST1 flew {}", odometer["FAST1"]);
+    }
+
+    #[test]
+    fn test_track_intersection() {
+        let northbound = Airplane::new("N1", "A320", 48.0, 14.0, 30000.0, 250.0, 0.0);
+        let eastbound = Airplane::new("E1", "A320", 48.5, 13.5, 30000.0, 250.0, 90.0);
+
+        let (lat, lng) = track_intersection(&northbound, &eastbound).unwrap();
+        assert!((lat - 48.5).abs() < 0.01, "latitude was {lat}");
+        assert!((lng - 14.0).abs() < 1e-9, "longitude was {lng}");
+        let (lat2, lng2) = track_intersection(&eastbound, &northbound).unwrap();
+        assert!((lat - lat2).abs() < 1e-9 && (lng - lng2).abs() < 1e-9);
+
+        let parallel = Airplane::new("N2", "A320", 48.0, 14.5, 30000.0, 250.0, 0.0);
+        assert_eq!(track_intersection(&northbound, &parallel), None);
+    }
+
+    #[test]
+    fn test_generator_density_bias() {
+        let config = GeneratorConfig { density_bias: 3.0, ..GeneratorConfig::default() };
+        let planes = generate_airplanes_with(1000, &config, 11).unwrap();
+
+        let mut distances_km: Vec<f64> = planes
+            .iter()
+            .map(|p| haversine_distance_nm(LNZ_LAT, LNZ_LNG, p.latitude, p.longitude) * KM_PER_NM)
+            .collect();
+        distances_km.sort_by(f64::total_cmp);
+        let median = distances_km[distances_km.len() / 2];
+        assert!(median < 0.3 * AIRSPACE_RADIUS_KM, "median distance was {median}km");
+    }
+
+    #[test]
+    fn test_top_alerts() {
+        let planes = vec![
+            Airplane::new("A1", "A320", 48.0, 14.0, 30000.0, 250.0, 0.0),
+            Airplane::new("A2", "A320", 48.0 + 4.0 / 60.0, 14.0, 30000.0, 250.0, 0.0),
+            Airplane::new("B1", "A320", 49.0, 14.0, 30000.0, 250.0, 0.0),
+            Airplane::new("B2", "A320", 49.0 + 1.0 / 60.0, 14.0, 30000.0, 250.0, 0.0),
+        ];
+
+        let top = top_alerts(&planes, 1);
+        assert_eq!(top.len(), 1);
+        assert_eq!((top[0].plane1_callsign.as_str(), top[0].plane2_callsign.as_str()), ("B1", "B2"));
+        assert_eq!(top_alerts(&planes, 10).len(), 2);
+    }
+
+    #[test]
+    fn test_generate_random_airplanes() {
+        let planes = generate_random_airplanes(50, 3);
+        assert_eq!(planes.len(), 50);
+        assert!(planes.iter().all(|p| !p.callsign.starts_with("TEST")));
+        assert!(validate_all(&planes).is_empty());
+    }
+
+    #[test]
+    fn test_approx_eq() {
+        let plane = Airplane::new("A1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 250.0, 90.0);
+
+        let mut nearly = plane.clone();
+        nearly.latitude += 1e-9;
+        assert!(plane.approx_eq(&nearly, 1e-6, 1.0));
+
+        let mut off = plane.clone();
+        off.latitude += 0.01;
+        assert!(!plane.approx_eq(&off, 1e-6, 1.0));
+
+        let mut renamed = plane.clone();
+        renamed.callsign = "A2".to_string();
+        assert!(!plane.approx_eq(&renamed, 1e-6, 1.0));
+    }
+
+    #[test]
+    fn test_approx_eq_with() {
+        let plane = Airplane::new("A1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 250.0, 90.0);
+        let eps = ApproxEps { coord: 1e-6, alt: 1.0, speed: 0.5, heading: 0.5 };
+
+        let mut nearly = plane.clone();
+        nearly.latitude += 1e-9;
+        nearly.speed += 0.2;
+        nearly.heading += 0.2;
+        assert!(plane.approx_eq_with(&nearly, &eps));
+        assert!(!plane.approx_eq(&nearly, 1e-6, 1.0));
+
+        let mut faster = plane.clone();
+        faster.speed += 1.0;
+        assert!(!plane.approx_eq_with(&faster, &eps));
+        let mut turned = plane.clone();
+        turned.heading += 1.0;
+        assert!(!plane.approx_eq_with(&turned, &eps));
+    }
+
+    #[test]
+    fn test_bin_by_bearing_from() {
+        let planes = vec![
+            Airplane::new("NORTH", "A320", LNZ_LAT + 0.5, LNZ_LNG, 30000.0, 250.0, 0.0),
+            Airplane::new("EAST", "A320", LNZ_LAT, LNZ_LNG + 0.5, 30000.0, 250.0, 0.0),
+            Airplane::new("NORTH2", "A320", LNZ_LAT + 0.3, LNZ_LNG + 0.01, 30000.0, 250.0, 0.0),
+        ];
+
+        let bins = bin_by_bearing_from((LNZ_LAT, LNZ_LNG), &planes, 30.0);
+        let callsigns = |sector: u32| -> Vec<&str> {
+            bins[&sector].iter().map(|p| p.callsign.as_str()).collect()
+        };
+        assert_eq!(bins.len(), 2);
+        assert_eq!(callsigns(0), vec!["NORTH", "NORTH2"]);
+        // Due east is bearing ~89.8°, just below the 90° boundary
+        assert_eq!(callsigns(2), vec!["EAST"]);
+    }
+
+    #[test]
+    fn test_intercept_heading() {
+        // Target 10nm north of the pursuer flying east
+        let target_lat = LNZ_LAT + 10.0 / 60.0;
+        let target = Airplane::new("TGT1", "A320", target_lat, LNZ_LNG, 30000.0, 200.0, 90.0);
+        let pursuer = Airplane::new("PUR1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 400.0, 0.0);
+
+        // Lead pursuit: sin(lead) = 200/400, so the heading is ~30° to the right of north
+        let heading = intercept_heading(&pursuer, &target).unwrap();
+        assert!((heading - 30.0).abs() < 1.0, "heading was {heading}");
+
+        // Target straight ahead on the same track
+        let ahead = Airplane::new("TGT2", "A320", target_lat, LNZ_LNG, 30000.0, 200.0, 0.0);
+        assert!(intercept_heading(&pursuer, &ahead).unwrap().abs() < 1e-6);
+
+        let slow = Airplane::new("SLOW1", "C172", LNZ_LAT, LNZ_LNG, 30000.0, 100.0, 0.0);
+        assert_eq!(intercept_heading(&slow, &target), None);
+    }
+
+    #[test]
+    fn test_geometric_altitude_ft() {
+        let mut plane = Airplane::new("P1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 250.0, 0.0);
+        assert_eq!(plane.altitude_type, AltitudeType::Pressure);
+        assert!((plane.geometric_altitude_ft(1013.25) - 30000.0).abs() < 1e-6);
+
+        // High pressure: the plane is higher than its pressure altitude
+        let altitude = plane.geometric_altitude_ft(1030.0);
+        assert!((altitude - 30350.0).abs() < 10.0, "altitude was {altitude}");
+        assert!(plane.geometric_altitude_ft(990.0) < 30000.0);
+
+        plane.altitude_type = AltitudeType::Geometric;
+        assert_eq!(plane.geometric_altitude_ft(1030.0), 30000.0);
+    }
+
+    #[test]
+    fn test_sector_entry_predictions() {
+        // Sector north of LNZ starting 10nm out
+        let sector = [
+            (LNZ_LAT + 10.0 / 60.0, LNZ_LNG - 0.5),
+            (LNZ_LAT + 10.0 / 60.0, LNZ_LNG + 0.5),
+            (LNZ_LAT + 1.0, LNZ_LNG + 0.5),
+            (LNZ_LAT + 1.0, LNZ_LNG - 0.5),
+        ];
+        // 5nm from the sector border at 300kn, enters after 60s when northbound
+        let start = LNZ_LAT + 5.0 / 60.0;
+        let planes = vec![
+            Airplane::new("INBOUND", "A320", start, LNZ_LNG, 30000.0, 300.0, 0.0),
+            Airplane::new("OUTBOUND", "A320", start, LNZ_LNG, 30000.0, 300.0, 180.0),
+            Airplane::new("INSIDE", "A320", LNZ_LAT + 0.5, LNZ_LNG, 30000.0, 300.0, 0.0),
+        ];
+
+        let predictions = sector_entry_predictions(&planes, &sector, 600.0, 5.0);
+        assert_eq!(predictions.len(), 1);
+        assert_eq!(predictions[0].0, "INBOUND");
+        assert!(predictions[0].1 > 0.0 && (predictions[0].1 - 60.0).abs() <= 5.0);
+    }
+
+    #[test]
+    fn test_sector_entry_predictions_invalid_step() {
+        let sector = [
+            (LNZ_LAT + 10.0 / 60.0, LNZ_LNG - 0.5),
+            (LNZ_LAT + 10.0 / 60.0, LNZ_LNG + 0.5),
+            (LNZ_LAT + 1.0, LNZ_LNG + 0.5),
+        ];
+        let planes = vec![Airplane::new("INBOUND", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 300.0, 0.0)];
+
+        for step_s in [0.0, -5.0, f64::NAN, f64::INFINITY] {
+            assert!(sector_entry_predictions(&planes, &sector, 600.0, step_s).is_empty());
+        }
+        assert!(sector_entry_predictions(&planes, &sector, f64::INFINITY, 5.0).is_empty());
+        assert!(sector_entry_predictions(&planes, &sector, f64::NAN, 5.0).is_empty());
+    }
+
+    #[test]
+    fn test_bearing_to_airport() {
+        let south = Airplane::new("S1", "A320", LNZ_LAT - 0.5, LNZ_LNG, 30000.0, 250.0, 90.0);
+        let bearing = south.bearing_to(LNZ_LAT, LNZ_LNG);
+        assert!(heading_difference_deg(bearing, 0.0) < 0.01, "bearing was {bearing}");
+
+        let west = Airplane::new("W1", "A320", LNZ_LAT, LNZ_LNG - 0.5, 30000.0, 250.0, 90.0);
+        assert!((west.bearing_to(LNZ_LAT, LNZ_LNG) - 90.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_generate_conflict_scenario() {
+        let planes = generate_conflict_scenario(4, 5);
+        assert_eq!(planes.len(), 4 * 2 + CONFLICT_SCENARIO_BACKGROUND_PLANES);
+        assert!(check_all_alerts(&planes).len() >= 4);
+
+        // Pairs stay below both ceilings, so advancing does not clamp and shift the geometry
+        for seed in 0..20 {
+            let planes = generate_conflict_scenario(4, seed);
+            assert!(planes.iter().all(|p| p.altitude <= service_ceiling_ft(&p.aircraft_type)));
+            let advanced = calculate_airplane_positions(&planes, 1.0);
+            assert!(planes.iter().zip(&advanced).all(|(p, a)| p.altitude == a.altitude));
+        }
+
+        let callsigns: HashSet<_> = planes.iter().map(|p| &p.callsign).collect();
+        assert_eq!(callsigns.len(), planes.len());
+        let ids: Vec<u64> = planes.iter().map(|p| p.id).collect();
+        assert_eq!(ids, (1..=planes.len() as u64).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_check_all_alerts_indexed() {
+        let planes = calculate_airplane_positions(&generate_demo_airplanes(), 60.0);
+        let indexed = check_all_alerts_indexed(&planes);
+        assert_eq!(indexed.len(), check_all_alerts(&planes).len());
+
+        for (i, j, alert) in &indexed {
+            assert!(i < j);
+            assert_eq!(planes[*i].callsign, alert.plane1_callsign);
+            assert_eq!(planes[*j].callsign, alert.plane2_callsign);
+        }
+        assert!(indexed.iter().any(|(i, j, _)| (*i, *j) == (0, 1)));
+    }
+
+    #[test]
+    fn test_position_update_over_north_pole() {
+        let plane = Airplane::new("POLE1", "B77W", 89.9, 10.0, 35000.0, 600.0, 0.0);
+
+        // 600kn for one minute is 10nm, i.e. 1/6° beyond the pole
+        let updated = calculate_airplane_positions(&[plane], 60.0);
+        assert!((updated[0].latitude - (180.0 - (89.9 + 10.0 / 60.0))).abs() < 1e-9);
+        assert!((-90.0..=90.0).contains(&updated[0].latitude));
+        assert!((updated[0].longitude - -170.0).abs() < 1e-9);
+        assert_eq!(updated[0].heading, 180.0);
+        assert!(updated[0].validate().is_ok());
+    }
+
+    #[test]
+    fn test_position_update_keeps_in_range_position() {
+        let parked = Airplane::new("PARK1", "A320", LNZ_LAT, LNZ_LNG, 0.0, 0.0, 90.0);
+        let updated = calculate_airplane_positions(std::slice::from_ref(&parked), 60.0);
+        assert_eq!(updated[0].latitude.to_bits(), parked.latitude.to_bits());
+        assert_eq!(updated[0].longitude.to_bits(), parked.longitude.to_bits());
+
+        let dateline = Airplane::new("PARK2", "A320", 0.0, 180.0, 0.0, 0.0, 90.0);
+        let updated = calculate_airplane_positions(&[dateline], 60.0);
+        assert_eq!(updated[0].longitude, 180.0);
+    }
+
+    #[test]
+    fn test_position_update_over_both_poles() {
+        let plane = Airplane::new("POLE2", "B77W", 0.0, 10.0, 35000.0, 600.0, 0.0);
+
+        // 300° of latitude: over the north pole, down to the south pole and 30° back up
+        let updated = calculate_airplane_positions(std::slice::from_ref(&plane), 300.0 * 60.0 * 6.0);
+        assert!((updated[0].latitude - -60.0).abs() < 1e-6);
+        assert!((updated[0].longitude - 10.0).abs() < 1e-6);
+        assert_eq!(updated[0].heading, 0.0);
+        assert!(updated[0].validate().is_ok());
+
+        // 460° of latitude: three pole crossings leave the plane on the opposite meridian
+        let updated = calculate_airplane_positions(&[plane], 460.0 * 60.0 * 6.0);
+        assert!((updated[0].latitude - 80.0).abs() < 1e-6);
+        assert!((updated[0].longitude - -170.0).abs() < 1e-6);
+        assert_eq!(updated[0].heading, 180.0);
+        assert!(updated[0].validate().is_ok());
+    }
+
+    #[test]
+    fn test_density_grid() {
+        let planes = vec![
+            Airplane::new("C1", "A320", 48.6, 14.6, 30000.0, 250.0, 0.0),
+            Airplane::new("C2", "A320", 48.65, 14.7, 30000.0, 250.0, 0.0),
+            Airplane::new("C3", "A320", 48.7, 14.65, 30000.0, 250.0, 0.0),
+            Airplane::new("OUT", "A320", 50.0, 14.6, 30000.0, 250.0, 0.0),
+        ];
+
+        let grid = density_grid(&planes, 48.0, 14.0, 49.0, 15.0, 2, 2);
+        assert_eq!(grid, vec![vec![0, 0], vec![0, 3]]);
+    }
+
+    #[test]
+    fn test_descent_profile() {
+        // 200nm south of LNZ at 30000ft: descend 90nm out
+        let far = Airplane::new("F1", "A320", LNZ_LAT - 200.0 / 60.0, LNZ_LNG, 30000.0, 450.0, 0.0);
+        assert!((descent_profile(&far, LNZ_LAT, LNZ_LNG, 3.0) - 90.0).abs() < 1e-9);
+
+        // Already inside the top-of-descent distance, which does not depend on the position
+        let near = Airplane::new("N1", "A320", LNZ_LAT - 50.0 / 60.0, LNZ_LNG, 30000.0, 450.0, 0.0);
+        assert!((descent_profile(&near, LNZ_LAT, LNZ_LNG, 3.0) - 90.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_compact_json_round_trip() {
+        let planes = generate_demo_airplanes();
+        let json = snapshot_to_compact_json(&planes);
+        assert!(json.starts_with(r#"{"fields":["callsign","aircraft_type","latitude""#));
+
+        let parsed = snapshot_from_compact_json(&json).unwrap();
+        assert_eq!(parsed.len(), planes.len());
+        for (original, parsed) in planes.iter().zip(&parsed) {
+            assert!(original.approx_eq(parsed, 1e-9, 1e-9));
+        }
+
+        let reordered = r#"{"fields":["aircraft_type","callsign"],"planes":[]}"#;
+        assert!(snapshot_from_compact_json(reordered).is_err());
+    }
+
+    #[test]
+    fn test_generate_multi_hub() {
+        // LNZ and Vienna (VIE)
+        let hubs = [(LNZ_LAT, LNZ_LNG), (48.110278, 16.569722)];
+        let planes = generate_multi_hub(&hubs, 30, 9);
+        assert_eq!(planes.len(), 60);
+
+        let max_distance_nm = AIRSPACE_RADIUS_KM / KM_PER_NM + 0.1;
+        for (index, (lat, lng)) in hubs.iter().enumerate() {
+            for plane in &planes[index * 30..(index + 1) * 30] {
+                let distance = haversine_distance_nm(*lat, *lng, plane.latitude, plane.longitude);
+                assert!(distance < max_distance_nm, "{} is {distance}nm from its hub", plane.callsign);
+            }
+        }
+
+        let callsigns: HashSet<_> = planes.iter().map(|p| &p.callsign).collect();
+        assert_eq!(callsigns.len(), planes.len());
+    }
+
+    #[test]
+    fn test_recent_alerts_suppresses_repeats() {
+        let planes = calculate_airplane_positions(&generate_demo_airplanes(), 60.0);
+        let alerts = check_all_alerts(&planes);
+        let mut recent = RecentAlerts::new();
+
+        assert_eq!(recent.novel(&alerts, 0.0, 30.0).len(), alerts.len());
+        recent.record(&alerts, 0.0);
+        assert!(recent.novel(&alerts, 10.0, 30.0).is_empty());
+        assert_eq!(recent.novel(&alerts, 45.0, 30.0).len(), alerts.len());
+
+        // The same conflict reported with swapped planes is not novel either
+        let mut swapped = alerts[0].clone();
+        std::mem::swap(&mut swapped.plane1_callsign, &mut swapped.plane2_callsign);
+        assert!(recent.novel(&[swapped], 10.0, 30.0).is_empty());
+    }
+
+    #[test]
+    fn test_cross_track_distance() {
+        // Due-east leg along the LNZ latitude (a great circle starting due east)
+        let leg_start = (LNZ_LAT, LNZ_LNG);
+        let leg_end = offset_position_along(LNZ_LAT, LNZ_LNG, 90.0, 1.0);
+
+        let left_lat = LNZ_LAT + 1.0 / 60.0;
+        let left = Airplane::new("L1", "A320", left_lat, LNZ_LNG, 30000.0, 250.0, 90.0);
+        let distance = cross_track_distance_nm(&left, leg_start, leg_end);
+        assert!((distance + 1.0).abs() < 0.01, "distance was {distance}");
+
+        let right_lat = LNZ_LAT - 1.0 / 60.0;
+        let right = Airplane::new("R1", "A320", right_lat, LNZ_LNG, 30000.0, 250.0, 90.0);
+        assert!((cross_track_distance_nm(&right, leg_start, leg_end) - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_detect_formations() {
+        let lat = |offset_nm: f64| LNZ_LAT + offset_nm / 60.0;
+        let planes = vec![
+            Airplane::new("LEAD", "A320", lat(0.0), LNZ_LNG, 30000.0, 300.0, 0.0),
+            Airplane::new("WING1", "A320", lat(0.5), LNZ_LNG, 30000.0, 300.0, 0.0),
+            Airplane::new("WING2", "A320", lat(1.0), LNZ_LNG, 30000.0, 300.0, 1.0),
+            // Close but crossing
+            Airplane::new("CROSS", "A320", lat(0.25), LNZ_LNG, 31000.0, 300.0, 90.0),
+            Airplane::new("FAR", "A320", lat(30.0), LNZ_LNG, 30000.0, 300.0, 0.0),
+        ];
+
+        let formations = detect_formations(&planes, 1.5, 5.0);
+        assert_eq!(formations, vec![vec!["LEAD", "WING1", "WING2"]]);
+    }
+
+    #[test]
+    fn test_simulate_with_alert_callback() {
+        let mut invocations = 0;
+        let mut steps_with_alerts = Vec::new();
+        simulate_with_alert_callback(generate_demo_airplanes(), 10.0, 12, |step, alerts| {
+            invocations += 1;
+            if alerts.iter().any(|a| a.plane1_callsign == "TEST001" && a.plane2_callsign == "TEST002") {
+                steps_with_alerts.push(step);
+            }
+        });
+
+        assert_eq!(invocations, 12);
+        // The test planes get within 5nm after ~15s
+        assert_eq!(steps_with_alerts.first(), Some(&1));
+    }
+
+    #[test]
+    fn test_min_vertical_separation_needed() {
+        let plane1 = Airplane::new("A1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 250.0, 0.0);
+        let plane2 = Airplane::new("A2", "A320", LNZ_LAT, LNZ_LNG, 30500.0, 250.0, 0.0);
+        let plane3 = Airplane::new("A3", "A320", LNZ_LAT, LNZ_LNG, 32000.0, 250.0, 0.0);
+        assert_eq!(min_vertical_separation_needed_ft(&plane1, &plane2), 500.0);
+        assert_eq!(min_vertical_separation_needed_ft(&plane1, &plane3), 0.0);
+    }
+
+    #[test]
+    fn test_analyze_replay() {
+        // Two planes converging head-on, passing at 1nm lateral offset and diverging again
+        let plane1 = Airplane::new("A1", "A320", LNZ_LAT - 0.2, LNZ_LNG, 30000.0, 360.0, 0.0);
+        let plane2_lng = LNZ_LNG + 1.0 / (60.0 * LNZ_LAT.to_radians().cos());
+        let plane2 = Airplane::new("A2", "A320", LNZ_LAT + 0.2, plane2_lng, 30000.0, 360.0, 180.0);
+        let initial = vec![plane1, plane2];
+
+        let snapshots: Vec<(f64, Vec<Airplane>)> = (0..=24)
+            .map(|i| {
+                let time_s = i as f64 * 10.0;
+                (time_s, calculate_airplane_positions(&initial, time_s))
+            })
+            .collect();
+
+        let events = analyze_replay(&snapshots, &AlertConfig::default());
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!((event.plane1_callsign.as_str(), event.plane2_callsign.as_str()), ("A1", "A2"));
+        assert!(event.start_s > 0.0 && event.end_s < 240.0 && event.start_s < event.end_s);
+        // Closest approach happens at t = 120s
+        assert!((event.min_distance_nm - 1.0).abs() < 0.01, "min was {}", event.min_distance_nm);
+    }
+
+    #[test]
+    fn test_sample_great_circle() {
+        // LNZ to New York (JFK)
+        let start = (LNZ_LAT, LNZ_LNG);
+        let end = (40.6413, -73.7781);
+        let points = sample_great_circle(start, end, 11);
+        assert_eq!(points.len(), 11);
+
+        let close = |a: (f64, f64), b: (f64, f64)| {
+            (a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9
+        };
+        assert!(close(points[0], start));
+        assert!(close(points[10], end));
+        assert!(close(points[5], great_circle_midpoint(start, end)));
+
+        // Evenly spaced along the route
+        let legs: Vec<f64> = points
+            .windows(2)
+            .map(|w| haversine_distance_nm(w[0].0, w[0].1, w[1].0, w[1].1))
+            .collect();
+        assert!(legs.iter().all(|leg| (leg - legs[0]).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_conflict_probability() {
+        // Head-on at the same level, 20nm apart, closing at 480kn
+        let west = Airplane::new("WEST", "A320", LNZ_LAT, LNZ_LNG - 10.0 / 60.0, 30000.0, 240.0, 90.0);
+        let east = Airplane::new("EAST", "A320", LNZ_LAT, LNZ_LNG + 10.0 / 60.0, 30000.0, 240.0, 270.0);
+        let converging = conflict_probability(&west, &east, 1.0, 300.0, 42);
+        assert!(converging > 0.9, "converging probability {converging}");
+
+        // Same pair flying away from each other
+        let west_out = Airplane { heading: 270.0, ..west.clone() };
+        let east_out = Airplane { heading: 90.0, ..east.clone() };
+        let diverging = conflict_probability(&west_out, &east_out, 1.0, 300.0, 42);
+        assert!(diverging < 0.05, "diverging probability {diverging}");
+
+        // Deterministic for a given seed
+        assert_eq!(converging, conflict_probability(&west, &east, 1.0, 300.0, 42));
+
+        // Invalid horizons are rejected instead of stepped through
+        assert_eq!(conflict_probability(&west, &east, 1.0, f64::INFINITY, 42), 0.0);
+        assert_eq!(conflict_probability(&west, &east, 1.0, f64::NAN, 42), 0.0);
+        assert_eq!(conflict_probability(&west, &east, 1.0, -300.0, 42), 0.0);
+    }
+
+    #[test]
+    fn test_generator_altitude_range() {
+        let config = GeneratorConfig::default().with_altitude_range(2000.0, 8000.0).unwrap();
+        let planes = generate_airplanes_with(50, &config, 11).unwrap();
+        assert_eq!(planes.len(), 50);
+        assert!(planes.iter().all(|p| (2000.0..=8000.0).contains(&p.altitude)));
+
+        let inverted = GeneratorConfig { min_alt: 8000.0, max_alt: 2000.0, ..config };
+        assert!(generate_airplanes_with(50, &inverted, 11).is_err());
+
+        assert_eq!(
+            GeneratorConfig::default().with_altitude_range(8000.0, 2000.0),
+            Err(GeneratorConfigError::InvalidAltitudeRange { min_alt: 8000.0, max_alt: 2000.0 })
+        );
+        assert!(GeneratorConfig::default().with_altitude_range(5000.0, 5000.0).is_err());
+        assert!(GeneratorConfig::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_merge_alerts() {
+        let plane1 = Airplane::new("AAA1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 300.0, 0.0);
+        let plane2 = Airplane::new("BBB2", "A320", LNZ_LAT, LNZ_LNG, 30500.0, 300.0, 0.0);
+        let plane3 = Airplane::new("CCC3", "A320", LNZ_LAT, LNZ_LNG, 30500.0, 300.0, 0.0);
+        let advisory = Alert::between(&plane1, &plane2, 4.0, 500.0);
+        let critical = Alert::between(&plane2, &plane1, 0.5, 500.0);
+        let other = Alert::between(&plane1, &plane3, 3.0, 500.0);
+
+        let merged = merge_alerts(vec![advisory.clone(), other.clone(), critical.clone()]);
+        assert_eq!(merged, vec![critical.clone(), other]);
+
+        // Order of the duplicates does not matter
+        assert_eq!(merge_alerts(vec![critical.clone(), advisory]), vec![critical]);
+    }
+
+    #[test]
+    fn test_index_by_callsign() {
+        let mut planes = generate_demo_airplanes();
+        let index = index_by_callsign(&planes);
+        assert_eq!(index["TEST001"].aircraft_type, "A320");
+        assert!(!index.contains_key("NOPE123"));
+
+        let mut duplicate = planes[0].clone();
+        duplicate.altitude = 12345.0;
+        planes.push(duplicate);
+        assert_eq!(index_by_callsign(&planes)["TEST001"].altitude, 12345.0);
+    }
+
+    #[test]
+    fn test_distance_remaining() {
+        // 20nm north, then 10nm east
+        let start = (LNZ_LAT, LNZ_LNG);
+        let turn = offset_position(LNZ_LAT, LNZ_LNG, 20.0, 0.0);
+        let end = offset_position(turn.0, turn.1, 0.0, 10.0);
+        let route = [start, turn, end];
+
+        let (lat, lng) = offset_position(LNZ_LAT, LNZ_LNG, 5.0, 0.0);
+        let plane = Airplane::new("RTE1", "A320", lat, lng, 30000.0, 300.0, 0.0);
+        let remaining = distance_remaining_nm(&plane, &route, 0);
+        assert!((remaining - 25.0).abs() < 0.1, "remaining was {remaining}");
+
+        assert_eq!(distance_remaining_nm(&plane, &route, 2), 0.0);
+    }
+
+    #[test]
+    fn test_metrics_text() {
+        let planes = calculate_airplane_positions(&generate_demo_airplanes(), 60.0);
+        let text = metrics_text(&planes);
+        let metric = |name: &str| -> f64 {
+            let line = text.lines().find(|line| line.starts_with(name)).unwrap();
+            line.split_whitespace().nth(1).unwrap().parse().unwrap()
+        };
+
+        assert_eq!(metric("flight_plane_count"), planes.len() as f64);
+        assert_eq!(metric("flight_alert_count"), check_all_alerts(&planes).len() as f64);
+        let test_pair = horizontal_distance_nm(&planes[0], &planes[1]);
+        let min_separation = metric("flight_min_separation_nm");
+        assert!(min_separation > 0.0 && min_separation <= test_pair + 1e-3);
+
+        assert!(!metrics_text(&planes[..1]).contains("flight_min_separation_nm"));
+    }
+
+    #[test]
+    fn test_paused_simulation() {
+        let mut simulation = Simulation::new(generate_demo_airplanes());
+        assert!(simulation.tick(60.0).iter().any(|a| a.plane1_callsign == "TEST001"));
+
+        simulation.paused = true;
+        let frozen = simulation.planes.clone();
+        let alerts = simulation.tick(60.0);
+        assert!(
+            alerts.iter().any(|a| a.plane1_callsign == "TEST001" && a.plane2_callsign == "TEST002")
+        );
+        assert_eq!(simulation.time_s, 60.0);
+        for (before, after) in frozen.iter().zip(&simulation.planes) {
+            assert_eq!((before.latitude, before.longitude), (after.latitude, after.longitude));
+        }
+
+        simulation.paused = false;
+        simulation.tick(1.0);
+        assert_ne!(simulation.planes[0].latitude, frozen[0].latitude);
+    }
+
+    #[test]
+    fn test_altitude_deviations() {
+        let mut low = Airplane::new("LOW1", "A320", LNZ_LAT, LNZ_LNG, 8500.0, 250.0, 0.0);
+        low.assigned_altitude_ft = Some(10000.0);
+        let mut level = Airplane::new("LVL1", "A320", LNZ_LAT, LNZ_LNG, 10200.0, 250.0, 0.0);
+        level.assigned_altitude_ft = Some(10000.0);
+        let unassigned = Airplane::new("FREE1", "A320", LNZ_LAT, LNZ_LNG, 3000.0, 250.0, 0.0);
+
+        let deviations = altitude_deviations(&[low, level, unassigned], 300.0);
+        assert_eq!(deviations, vec![("LOW1".to_string(), -1500.0)]);
+    }
+
+    #[test]
+    fn test_closest_pair() {
+        let lat = |offset_nm: f64| LNZ_LAT + offset_nm / 60.0;
+        let planes = vec![
+            Airplane::new("FAR1", "A320", lat(0.0), LNZ_LNG, 30000.0, 300.0, 0.0),
+            Airplane::new("NEAR1", "A320", lat(10.0), LNZ_LNG, 20000.0, 300.0, 0.0),
+            Airplane::new("FAR2", "A320", lat(20.0), LNZ_LNG, 30000.0, 300.0, 0.0),
+            Airplane::new("NEAR2", "A320", lat(11.5), LNZ_LNG, 36000.0, 300.0, 0.0),
+        ];
+        let (plane1, plane2, distance) = closest_pair(&planes).unwrap();
+        assert_eq!((plane1.as_str(), plane2.as_str()), ("NEAR1", "NEAR2"));
+        assert!((distance - 1.5).abs() < 0.01);
+
+        assert_eq!(closest_pair(&planes[..1]), None);
+    }
+
+    #[test]
+    fn test_alert_config_edge_inclusive() {
+        let plane1 = Airplane::new("EDGE1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 250.0, 0.0);
+        let plane2 = Airplane::new("EDGE2", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 250.0, 180.0);
+        let planes = [plane1, plane2];
+        let exactly_at_threshold = |_: &Airplane, _: &Airplane| ALERT_DISTANCE_NM;
+
+        let exclusive = AlertConfig::default();
+        assert!(check_all_alerts_with(&planes, &exclusive, exactly_at_threshold).is_empty());
+        assert!(!exclusive.is_loss_of_separation(5.0, 0.0));
+
+        let inclusive = AlertConfig { edge_inclusive: true, ..AlertConfig::default() };
+        let alerts = check_all_alerts_with(&planes, &inclusive, exactly_at_threshold);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].distance_nm, 5.0);
+        assert!(inclusive.is_loss_of_separation(5.0, 1000.0));
+        assert!(!inclusive.is_loss_of_separation(5.000001, 0.0));
+    }
+
+    #[test]
+    fn test_risk_score() {
+        let lat = |offset_nm: f64| LNZ_LAT + offset_nm / 60.0;
+        // 3nm apart, 500ft apart, head-on at 960kn closure
+        let near1 = Airplane::new("NEAR1", "A320", lat(0.0), LNZ_LNG, 30000.0, 480.0, 0.0);
+        let near2 = Airplane::new("NEAR2", "A320", lat(3.0), LNZ_LNG, 30500.0, 480.0, 180.0);
+        // 40nm apart, 4000ft apart, flying away from each other
+        let far1 = Airplane::new("FAR1", "A320", lat(0.0), LNZ_LNG, 30000.0, 300.0, 180.0);
+        let far2 = Airplane::new("FAR2", "A320", lat(40.0), LNZ_LNG, 34000.0, 300.0, 0.0);
+
+        let high = risk_score(&near1, &near2);
+        let low = risk_score(&far1, &far2);
+        assert!(high > low, "{high} should exceed {low}");
+        assert!((0.0..=1.0).contains(&high) && (0.0..=1.0).contains(&low));
+        assert!((high - risk_score(&near2, &near1)).abs() < 1e-9);
+
+        // Two identical co-located planes reach the proximity maximum without closure
+        assert!((risk_score(&near1, &near1) - 0.7).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_generate_for_hour() {
+        let night = generate_for_hour(3, 40, 5);
+        let morning = generate_for_hour(8, 40, 5);
+        assert!(night.len() < morning.len());
+        assert_eq!(morning.len(), 40);
+        assert_eq!(generate_for_hour(27, 40, 5).len(), night.len());
+    }
+
+    #[test]
+    fn test_time_separation() {
+        let leader = Airplane::new("LEAD", "A320", LNZ_LAT + 2.0 / 60.0, LNZ_LNG, 3000.0, 120.0, 0.0);
+        let follower = Airplane::new("FOLL", "A320", LNZ_LAT, LNZ_LNG, 3000.0, 120.0, 0.0);
+        let separation = time_separation_s(&leader, &follower).unwrap();
+        assert!((separation - 60.0).abs() < 0.5, "separation was {separation}");
+
+        assert_eq!(time_separation_s(&follower, &leader), None);
+    }
+
+    #[test]
+    fn test_terrain_conflicts() {
+        // Alps to the south of the LNZ latitude, flat land to the north
+        struct Mountains;
+        impl TerrainModel for Mountains {
+            fn elevation_ft(&self, lat: f64, _lng: f64) -> f64 {
+                if lat < LNZ_LAT { 9000.0 } else { 1000.0 }
+            }
+        }
+
+        let planes = vec![
+            Airplane::new("LOW1", "A320", LNZ_LAT - 0.5, LNZ_LNG, 9500.0, 250.0, 0.0),
+            Airplane::new("HIGH1", "A320", LNZ_LAT - 0.5, LNZ_LNG, 12000.0, 250.0, 0.0),
+            Airplane::new("FLAT1", "A320", LNZ_LAT + 0.5, LNZ_LNG, 9500.0, 250.0, 0.0),
+        ];
+        assert_eq!(terrain_conflicts(&planes, &Mountains, 1000.0), vec!["LOW1".to_string()]);
+        assert!(terrain_conflicts(&planes, &FlatTerrain::default(), 1000.0).is_empty());
+    }
+
+    #[test]
+    fn test_write_snapshot() {
+        let planes = generate_demo_airplanes();
+        let mut buffer = Vec::new();
+        write_snapshot(&mut buffer, &planes, 42.5).unwrap();
+
+        let parsed: serde_json::Value = serde_json::from_slice(&buffer).unwrap();
+        assert_eq!(parsed["time_s"], 42.5);
+        let parsed_planes = parsed["planes"].as_array().unwrap();
+        assert_eq!(parsed_planes.len(), planes.len());
+        assert_eq!(parsed_planes[0]["callsign"], "TEST001");
+        assert_eq!(parsed_planes[1]["altitude"], 29500.0);
+    }
+
+    #[test]
+    fn test_alert_geometry() {
+        let planes = calculate_airplane_positions(&generate_demo_airplanes(), 60.0);
+        let alert = check_alert_between_planes(&planes[0], &planes[1]).unwrap();
+        assert_eq!(alert.geometry, ConflictGeometry::HeadOn);
+
+        let plane =
+            |heading: f64| Airplane::new("GEO1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 250.0, heading);
+        assert_eq!(conflict_geometry(&plane(10.0), &plane(350.0)), ConflictGeometry::Overtaking);
+        assert_eq!(conflict_geometry(&plane(0.0), &plane(90.0)), ConflictGeometry::Crossing);
+        assert_eq!(conflict_geometry(&plane(45.0), &plane(200.0)), ConflictGeometry::HeadOn);
+
+        let csv = format!("{ALERT_CSV_HEADER}\nA,B,1,1,Warning,Sideways,false\n");
+        let err = alerts_from_csv(&csv);
+        assert_eq!(err.unwrap_err().message, "invalid geometry 'Sideways'");
+    }
+
+    #[test]
+    fn test_test_plane_defaults() {
+        let plane = test_plane("TP1", LNZ_LAT, LNZ_LNG, 12000.0, 270.0);
+        assert_eq!(plane.callsign, "TP1");
+        assert_eq!((plane.latitude, plane.longitude), (LNZ_LAT, LNZ_LNG));
+        assert_eq!((plane.altitude, plane.heading), (12000.0, 270.0));
+        assert_eq!(plane.speed, TEST_PLANE_SPEED_KN);
+        assert_eq!(plane.aircraft_type, TEST_PLANE_TYPE);
+        assert!(plane.validate().is_ok());
+    }
+
+    #[test]
+    fn test_standard_rate_turn() {
+        let plane = test_plane("TURN1", LNZ_LAT, LNZ_LNG, 10000.0, 90.0);
+        let turned = standard_rate_turn(&plane, 180.0, 30.0);
+        assert_eq!(turned.heading, 180.0);
+        assert_eq!(turned.last_update_s, plane.last_update_s + 30.0);
+
+        // A quarter circle to the right ends one turn radius east and one south
+        let radius_nm = plane.speed / 3600.0 / 3.0_f64.to_radians();
+        let (north, east) =
+            local_offset_nm(plane.latitude, plane.longitude, turned.latitude, turned.longitude);
+        assert!((north + radius_nm).abs() < 0.01, "north was {north}");
+        assert!((east - radius_nm).abs() < 0.01, "east was {east}");
+
+        // Shortest arc: 010° to 350° turns left, 15° in the first 5s
+        let plane = test_plane("TURN2", LNZ_LAT, LNZ_LNG, 10000.0, 10.0);
+        let left = standard_rate_turn(&plane, 350.0, 5.0);
+        assert!((left.heading - 355.0).abs() < 1e-9);
+        assert_eq!(left.last_update_s, plane.last_update_s + 5.0);
+    }
+
+    #[test]
+    fn test_capacity_utilization() {
+        let separated = vec![
+            test_plane("CAP1", LNZ_LAT, LNZ_LNG, 10000.0, 0.0),
+            test_plane("CAP2", LNZ_LAT + 0.5, LNZ_LNG, 10000.0, 0.0),
+        ];
+        assert_eq!(capacity_utilization(&separated, 10), 0.2);
+
+        let conflicting = vec![
+            test_plane("CAP1", LNZ_LAT, LNZ_LNG, 10000.0, 0.0),
+            test_plane("CAP2", LNZ_LAT + 1.0 / 60.0, LNZ_LNG, 10000.0, 0.0),
+        ];
+        assert_eq!(capacity_utilization(&conflicting, 10), 0.3);
+        assert_eq!(capacity_utilization(&conflicting, 2), 1.0);
+        assert_eq!(capacity_utilization(&[], 10), 0.0);
+    }
+
+    #[test]
+    fn test_to_fr24_rows() {
+        let planes = generate_demo_airplanes();
+        let rows = to_fr24_rows(&planes);
+        assert_eq!(rows.as_object().unwrap().len(), planes.len());
+        assert_eq!(
+            rows["TEST002"],
+            serde_json::json!([48.188992, 14.191473, 0.0, 29500.0, 120.0, "", "", "B738", "TEST002"])
+        );
+    }
+
+    #[test]
+    fn test_simulate_with_spawns() {
+        let snapshots = simulate_with_spawns(generate_demo_airplanes_count(2), 10.0, 200, 0.5, 3);
+        assert_eq!(snapshots.len(), 200);
+        assert!(snapshots.windows(2).all(|w| w[1].len() >= w[0].len()));
+
+        // About 100 spawned planes expected on top of the two initial ones
+        let spawned = snapshots.last().unwrap().len() - 2;
+        assert!((80..=120).contains(&spawned), "spawned {spawned}");
+
+        // New planes appear on the boundary and fly inbound
+        let step = snapshots.windows(2).position(|w| w[1].len() > w[0].len()).unwrap() + 1;
+        let spawned_plane = snapshots[step].last().unwrap();
+        let later = &snapshots[step + 1][snapshots[step].len() - 1];
+        let distance =
+            |p: &Airplane| haversine_distance_nm(LNZ_LAT, LNZ_LNG, p.latitude, p.longitude);
+        assert!((distance(spawned_plane) - AIRSPACE_RADIUS_KM / KM_PER_NM).abs() < 0.5);
+        assert!(distance(later) < distance(spawned_plane));
+
+        let callsigns: HashSet<_> = snapshots[199].iter().map(|p| &p.callsign).collect();
+        assert_eq!(callsigns.len(), snapshots[199].len());
+    }
+
+    #[test]
+    fn test_prune_outside() {
+        let planes = vec![
+            test_plane("IN1", LNZ_LAT, LNZ_LNG, 10000.0, 0.0),
+            test_plane("OUT1", LNZ_LAT, LNZ_LNG, 10000.0, 90.0),
+            test_plane("IN2", LNZ_LAT + 0.5, LNZ_LNG, 10000.0, 0.0),
+        ];
+        // One hour at 250kn takes OUT1 far outside the 50nm radius; the others stay put
+        let mut moved = planes.clone();
+        moved[1] = PositionModel::default().advance(&planes[1], 3600.0);
+
+        let remaining = prune_outside(&moved, (LNZ_LAT, LNZ_LNG), 50.0);
+        let callsigns: Vec<&str> = remaining.iter().map(|p| p.callsign.as_str()).collect();
+        assert_eq!(callsigns, vec!["IN1", "IN2"]);
+    }
+
+    #[test]
+    fn test_specific_energy() {
+        let slow = Airplane::new("SLOW1", "A320", LNZ_LAT, LNZ_LNG, 10000.0, 150.0, 0.0);
+        let fast = Airplane::new("FAST1", "A320", LNZ_LAT, LNZ_LNG, 10000.0, 450.0, 0.0);
+        assert!(fast.specific_energy_ft() > slow.specific_energy_ft());
+
+        // 300kn is about 506ft/s, worth roughly 3980ft of height
+        let plane = Airplane::new("NRG1", "A320", LNZ_LAT, LNZ_LNG, 0.0, 300.0, 0.0);
+        assert!((plane.specific_energy_ft() - 3983.0).abs() < 5.0);
+    }
+
+    #[test]
+    fn test_generated_ids_are_unique() {
+        let planes = generate_unique_fleet(30, 8);
+        let ids: HashSet<u64> = planes.iter().map(|p| p.id).collect();
+        assert_eq!(ids.len(), planes.len());
+        assert_eq!((planes[0].id, planes[1].id), (1, 2));
+        assert!(!ids.contains(&0));
+
+        let planes = generate_multi_hub(&[(LNZ_LAT, LNZ_LNG), (48.110278, 16.569722)], 10, 8);
+        let ids: Vec<u64> = planes.iter().map(|p| p.id).collect();
+        assert_eq!(ids, (1..=20).collect::<Vec<_>>());
+
+        // Spawned planes continue after the highest initial id, even if callsigns repeat
+        let mut initial = generate_demo_airplanes_count(2);
+        initial[1].callsign = initial[0].callsign.clone();
+        initial[1].id = 5;
+        let last = simulate_with_spawns(initial, 10.0, 20, 1.0, 8).pop().unwrap();
+        let ids: Vec<u64> = last.iter().map(|p| p.id).collect();
+        assert_eq!(ids, [1, 5].into_iter().chain(6..26).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_interpolate_snapshots_by_id_without_ids() {
+        // Airplane::new leaves every id at 0
+        let before = vec![
+            test_plane("A1", LNZ_LAT, LNZ_LNG, 10000.0, 0.0),
+            test_plane("B1", LNZ_LAT + 1.0, LNZ_LNG, 20000.0, 0.0),
+        ];
+        let after = calculate_airplane_positions(&before, 60.0);
+
+        let halfway = interpolate_snapshots_by_id(&before, &after, 0.5);
+        let callsigns: Vec<&str> = halfway.iter().map(|plane| plane.callsign.as_str()).collect();
+        assert_eq!(callsigns, ["A1", "B1"]);
+        assert_eq!(halfway[0].altitude, 10000.0);
+        assert_eq!(halfway[1].altitude, 20000.0);
+
+        // Duplicate ids fall back to callsigns as well
+        let mut numbered = before.clone();
+        numbered[0].id = 1;
+        numbered[1].id = 2;
+        let mut duplicates = after.clone();
+        duplicates.iter_mut().for_each(|plane| plane.id = 1);
+        let halfway = interpolate_snapshots_by_id(&numbered, &duplicates, 0.5);
+        let callsigns: Vec<&str> = halfway.iter().map(|plane| plane.callsign.as_str()).collect();
+        assert_eq!(callsigns, ["A1", "B1"]);
+    }
+
+    #[test]
+    fn test_interpolate_snapshots_by_id() {
+        let before = generate_demo_airplanes_count(5);
+        let mut after = calculate_airplane_positions(&before, 60.0);
+        for plane in &mut after {
+            plane.callsign.push('X');
+        }
+        after[0].heading = 350.0;
+        after[0].altitude += 1000.0;
+
+        // Callsigns differ, so matching by callsign finds nothing
+        assert!(interpolate_snapshots(&before, &after, 0.5).is_empty());
+
+        let halfway = interpolate_snapshots_by_id(&before, &after, 0.5);
+        assert_eq!(halfway.len(), 5);
+        let moved = calculate_airplane_positions(&before, 30.0);
+        for (plane, expected) in halfway.iter().zip(&moved) {
+            assert!((plane.latitude - expected.latitude).abs() < 1e-9);
+            assert!((plane.longitude - expected.longitude).abs() < 1e-9);
+        }
+        assert_eq!(halfway[0].callsign, "TEST001X");
+        assert_eq!(halfway[0].altitude, 30500.0);
+        // Shortest arc from 180° to 350° turns right through 265°
+        assert!((halfway[0].heading - 265.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_alert_config_look_ahead() {
+        // Head-on at the same level, 10nm apart and closing at 480kn: breach in about 38s
+        let (west_lat, west_lng) = offset_position(LNZ_LAT, LNZ_LNG, 0.0, -5.0);
+        let (east_lat, east_lng) = offset_position(LNZ_LAT, LNZ_LNG, 0.0, 5.0);
+        let west = Airplane::new("WEST", "A320", west_lat, west_lng, 30000.0, 240.0, 90.0);
+        let east = Airplane::new("EAST", "A320", east_lat, east_lng, 30000.0, 240.0, 270.0);
+        let planes = [west, east];
+        assert!(check_all_alerts(&planes).is_empty());
+
+        let config = AlertConfig { look_ahead_s: Some(60.0), ..AlertConfig::default() };
+        let alerts = check_all_alerts_with(&planes, &config, horizontal_distance_nm);
+        assert_eq!(alerts.len(), 1);
+        assert!(alerts[0].predicted);
+        assert!(alerts[0].distance_nm < ALERT_DISTANCE_NM);
+
+        let short = AlertConfig { look_ahead_s: Some(20.0), ..AlertConfig::default() };
+        assert!(check_all_alerts_with(&planes, &short, horizontal_distance_nm).is_empty());
+
+        // Current conflicts are not reported as predicted
+        let current = calculate_airplane_positions(&planes, 60.0);
+        let alerts = check_all_alerts_with(&current, &config, horizontal_distance_nm);
+        assert!(!alerts[0].predicted);
+
+        let csv = alerts_to_csv(&check_all_alerts_with(&planes, &config, horizontal_distance_nm));
+        assert!(alerts_from_csv(&csv).unwrap()[0].predicted);
+
+        // Windows that are not positive and finite are ignored instead of stepped through
+        for window_s in [f64::INFINITY, f64::NAN, -60.0, 0.0] {
+            let invalid = AlertConfig { look_ahead_s: Some(window_s), ..AlertConfig::default() };
+            assert!(check_all_alerts_with(&planes, &invalid, horizontal_distance_nm).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_planes_by_distance_from() {
+        let planes = generate_unique_fleet(25, 4);
+        let sorted = planes_by_distance_from(&planes, LNZ_LAT, LNZ_LNG);
+        assert_eq!(sorted.len(), planes.len());
+        assert!(sorted.windows(2).all(|w| w[0].1 <= w[1].1));
+
+        let (nearest, distance) = sorted[0];
+        let expected = haversine_distance_nm(LNZ_LAT, LNZ_LNG, nearest.latitude, nearest.longitude);
+        assert_eq!(distance, expected);
+    }
+
+    #[test]
+    fn test_conflict_hotspots() {
+        // The TEST pair converges over LNZ and stays in conflict for several snapshots
+        let initial = generate_demo_airplanes();
+        let snapshots: Vec<Vec<Airplane>> = (0..10)
+            .map(|i| calculate_airplane_positions(&initial, 60.0 + 10.0 * i as f64))
+            .collect();
+        let hotspots = conflict_hotspots(&snapshots, 0.5);
+
+        let lnz_cell = ((LNZ_LAT / 0.5).floor() as i64, (LNZ_LNG / 0.5).floor() as i64);
+        assert!(hotspots[&lnz_cell] >= 10);
+        let total: usize = hotspots.values().sum();
+        let expected: usize = snapshots.iter().map(|planes| check_all_alerts(planes).len()).sum();
+        assert_eq!(total, expected);
+    }
+
+    #[test]
+    fn test_intercept_localizer() {
+        // Runway 27 at LNZ: final course 270°, approaching from the east
+        let threshold = (LNZ_LAT, LNZ_LNG);
+        let at = |north_nm: f64, east_nm: f64| {
+            let (lat, lng) = offset_position(LNZ_LAT, LNZ_LNG, north_nm, east_nm);
+            test_plane("APP1", lat, lng, 3000.0, 270.0)
+        };
+
+        // North of the centerline is right of the final course: turn left towards it
+        assert_eq!(intercept_localizer(&at(3.0, 10.0), threshold, 270.0), 240.0);
+        assert_eq!(intercept_localizer(&at(-3.0, 10.0), threshold, 270.0), 300.0);
+        assert_eq!(intercept_localizer(&at(0.0, 10.0), threshold, 270.0), 270.0);
+        assert_eq!(intercept_localizer(&at(-2.0, -2.0), threshold, 10.0), 40.0);
+    }
+
+    #[test]
+    fn test_age_s() {
+        let mut plane = test_plane("AGE1", LNZ_LAT, LNZ_LNG, 10000.0, 0.0);
+        plane.last_update_s = 95.0;
+        assert_eq!(plane.age_s(100.0), 5.0);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&snapshot_to_json_with_age(&[plane], 100.0)).unwrap();
+        assert_eq!(json[0]["age_s"], 5.0);
+        assert_eq!(json[0]["last_update_s"], 95.0);
+        assert_eq!(json[0]["callsign"], "AGE1");
+    }
+
+    #[test]
+    fn test_position_updates_stamp_last_update() {
+        let plane = test_plane("AGE2", LNZ_LAT, LNZ_LNG, 10000.0, 0.0);
+
+        let moved = calculate_airplane_positions(std::slice::from_ref(&plane), 30.0);
+        assert_eq!(moved[0].last_update_s, 30.0);
+        assert_eq!(moved[0].age_s(40.0), 10.0);
+        let moved = PositionModel::default().advance(&moved[0], 15.0);
+        assert_eq!(moved.age_s(45.0), 0.0);
+
+        let mut clock = SimClock::new(20.0);
+        clock.advance(100.0);
+        let stepped = clock.step(vec![plane.clone()]);
+        assert_eq!(stepped[0].age_s(clock.time_s), 0.0);
+
+        let mut simulation = Simulation::new(vec![plane]);
+        simulation.tick(10.0);
+        simulation.tick(10.0);
+        assert_eq!(simulation.planes[0].age_s(simulation.time_s), 0.0);
+        simulation.paused = true;
+        simulation.tick(10.0);
+        assert_eq!(simulation.planes[0].age_s(25.0), 5.0);
+    }
+
+    #[test]
+    fn test_runway_incursions() {
+        // Simplified east-west runway box around LNZ
+        let runway = [
+            (LNZ_LAT - 0.0005, LNZ_LNG - 0.02),
+            (LNZ_LAT - 0.0005, LNZ_LNG + 0.02),
+            (LNZ_LAT + 0.0005, LNZ_LNG + 0.02),
+            (LNZ_LAT + 0.0005, LNZ_LNG - 0.02),
+        ];
+        let lining_up = test_plane("RWY1", LNZ_LAT, LNZ_LNG - 0.015, 0.0, 270.0);
+        let landing = test_plane("RWY2", LNZ_LAT, LNZ_LNG + 0.01, 200.0, 270.0);
+        let overflying = test_plane("OVR1", LNZ_LAT, LNZ_LNG, 3000.0, 270.0);
+        let taxiing = test_plane("TAX1", LNZ_LAT + 0.002, LNZ_LNG, 0.0, 90.0);
+
+        let planes = vec![lining_up.clone(), landing, overflying.clone(), taxiing.clone()];
+        assert_eq!(runway_incursions(&planes, &runway), vec!["RWY1", "RWY2"]);
+        assert!(runway_incursions(&[lining_up, overflying, taxiing], &runway).is_empty());
+    }
+
+    #[test]
+    fn test_distances_from() {
+        let planes = generate_unique_fleet(40, 12);
+        let distances = distances_from(LNZ_LAT, LNZ_LNG, &planes);
+        let expected: Vec<f64> = planes
+            .iter()
+            .map(|p| haversine_distance_nm(LNZ_LAT, LNZ_LNG, p.latitude, p.longitude))
+            .collect();
+        assert_eq!(distances, expected);
+    }
+
+    #[test]
+    fn test_shuffle_planes() {
+        let original = generate_conflict_scenario(4, 21);
+        let mut shuffled = original.clone();
+        shuffle_planes(&mut shuffled, 5);
+
+        let callsigns = |planes: &[Airplane]| {
+            let mut callsigns: Vec<String> = planes.iter().map(|p| p.callsign.clone()).collect();
+            callsigns.sort();
+            callsigns
+        };
+        assert_eq!(callsigns(&shuffled), callsigns(&original));
+        let order = |planes: &[Airplane]| planes.iter().map(|p| p.id).collect::<Vec<_>>();
+        assert_ne!(order(&shuffled), order(&original));
+
+        let mut again = original.clone();
+        shuffle_planes(&mut again, 5);
+        assert_eq!(order(&again), order(&shuffled));
+
+        let pairs = |planes: &[Airplane]| -> HashSet<(String, String)> {
+            check_all_alerts(planes).iter().map(alert_pair_key).collect()
+        };
+        assert!(!pairs(&original).is_empty());
+        assert_eq!(pairs(&shuffled), pairs(&original));
+    }
+
+    #[test]
+    fn test_wind_correction_heading() {
+        // 20kn from the east while tracking north at 120kn: crab right into the wind
+        let crosswind = Wind { from_deg: 90.0, speed_kn: 20.0 };
+        let heading = wind_correction_heading(0.0, 120.0, &crosswind).unwrap();
+        assert!((heading - (1.0_f64 / 6.0).asin().to_degrees()).abs() < 1e-9);
+
+        let from_west = Wind { from_deg: 270.0, speed_kn: 20.0 };
+        let heading = wind_correction_heading(0.0, 120.0, &from_west).unwrap();
+        assert!((heading - (360.0 - 9.594)).abs() < 0.01);
+
+        let headwind = Wind { from_deg: 0.0, speed_kn: 30.0 };
+        assert_eq!(wind_correction_heading(0.0, 120.0, &headwind), Some(0.0));
+        let storm = Wind { from_deg: 90.0, speed_kn: 150.0 };
+        assert_eq!(wind_correction_heading(0.0, 120.0, &storm), None);
+    }
+
+    #[test]
+    fn test_service_ceiling_clamps_altitude() {
+        assert_eq!(service_ceiling_ft("UNKNOWN"), 41000.0);
+        assert!(service_ceiling_ft("AT76") < service_ceiling_ft("B738"));
+
+        let atr = Airplane::new("ATR1", "AT76", LNZ_LAT, LNZ_LNG, 30000.0, 250.0, 0.0);
+        let jet = Airplane::new("JET1", "B789", LNZ_LAT, LNZ_LNG, 42000.0, 450.0, 0.0);
+        let updated = calculate_airplane_positions(&[atr, jet], 10.0);
+        assert_eq!(updated[0].altitude, 25000.0);
+        assert_eq!(updated[1].altitude, 42000.0);
+
+        // Generated planes already respect the ceiling, so the first update keeps the altitude
+        let planes = generate_unique_fleet(200, 2);
+        assert!(planes.iter().all(|p| p.altitude <= service_ceiling_ft(&p.aircraft_type)));
+        assert!(planes.iter().any(|p| p.aircraft_type == "AT76"));
+        let updated = calculate_airplane_positions(&planes, 0.0);
+        assert!(planes.iter().zip(&updated).all(|(p, u)| p.altitude == u.altitude));
+
+        // A band above the turboprop ceilings only produces types that can fly there
+        let config = GeneratorConfig::default().with_altitude_range(30000.0, 35000.0).unwrap();
+        let planes = generate_airplanes_with(200, &config, 2).unwrap();
+        assert!(planes.iter().all(|p| (30000.0..=35000.0).contains(&p.altitude)));
+        assert!(planes.iter().all(|p| !matches!(p.aircraft_type.as_str(), "AT76" | "DH8D")));
+        assert_eq!(
+            GeneratorConfig::default().with_altitude_range(44000.0, 45000.0),
+            Err(GeneratorConfigError::AboveServiceCeiling { min_alt: 44000.0, ceiling_ft: 43100.0 })
+        );
+    }
+
+    #[test]
+    fn test_alert_bearing_distribution() {
+        let (north_lat, north_lng) = offset_position(LNZ_LAT, LNZ_LNG, 2.0, 0.0);
+        let (east_lat, east_lng) = offset_position(LNZ_LAT, LNZ_LNG, 0.0, 2.0);
+        let planes = vec![
+            test_plane("NORTH", north_lat, north_lng, 30000.0, 0.0),
+            test_plane("FAR", LNZ_LAT - 1.0, LNZ_LNG, 30000.0, 0.0),
+            test_plane("EAST", east_lat, east_lng, 30000.0, 0.0),
+        ];
+        let bearings = alert_bearing_distribution((LNZ_LAT, LNZ_LNG), &planes);
+        assert_eq!(bearings.len(), 2);
+        assert!(bearings[0] < 0.1 || bearings[0] > 359.9, "north bearing {}", bearings[0]);
+        assert!((bearings[1] - 90.0).abs() < 0.1, "east bearing {}", bearings[1]);
+    }
+
+    #[test]
+    fn test_viewport_snapshot_json() {
+        let planes = calculate_airplane_positions(&generate_demo_airplanes(), 60.0);
+        let bbox = (LNZ_LAT - 0.1, LNZ_LNG - 0.1, LNZ_LAT + 0.1, LNZ_LNG + 0.1);
+        let json: serde_json::Value =
+            serde_json::from_str(&viewport_snapshot_json(&planes, bbox, 60.0)).unwrap();
+
+        assert_eq!(json["time_s"], 60.0);
+        let visible: Vec<&str> = json["planes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["callsign"].as_str().unwrap())
+            .collect();
+        let expected = planes_in_bbox(&planes, bbox);
+        assert_eq!(visible.len(), expected.len());
+        assert!(visible.contains(&"TEST001") && visible.contains(&"TEST002"));
+        for plane in &planes {
+            let inside = (bbox.0..=bbox.2).contains(&plane.latitude)
+                && (bbox.1..=bbox.3).contains(&plane.longitude);
+            assert_eq!(visible.contains(&plane.callsign.as_str()), inside);
+        }
+        assert_eq!(json["alerts"].as_array().unwrap().len(), check_all_alerts(&expected).len());
+    }
+
+    #[test]
+    fn test_avoidance_turn() {
+        // Head-on at 480kn closure, 20nm apart
+        let (lat, lng) = offset_position(LNZ_LAT, LNZ_LNG, 20.0, 0.0);
+        let ownship = Airplane::new("OWN1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 240.0, 0.0);
+        let intruder = Airplane::new("INT1", "A320", lat, lng, 30000.0, 240.0, 180.0);
+
+        let turn = avoidance_turn_deg(&ownship, &intruder, 5.0, 300.0).unwrap();
+        assert!(turn > 0.0 && turn < 90.0, "turn was {turn}");
+        let turned = Airplane { heading: turn, ..ownship.clone() };
+        assert!(min_separation_within_nm(&turned, &intruder, 300.0) >= 5.0);
+        let smaller = Airplane { heading: turn - 1.0, ..ownship.clone() };
+        assert!(min_separation_within_nm(&smaller, &intruder, 300.0) < 5.0);
+
+        // Already clear when flying away from each other
+        let away = Airplane { heading: 180.0, ..ownship.clone() };
+        assert_eq!(avoidance_turn_deg(&away, &intruder, 5.0, 300.0), None);
+
+        // An intruder already inside the protected radius cannot be resolved by turning
+        let (lat, lng) = offset_position(LNZ_LAT, LNZ_LNG, 0.4, 0.0);
+        let inside = Airplane::new("INT2", "A320", lat, lng, 30000.0, 240.0, 180.0);
+        assert_eq!(avoidance_turn_deg(&ownship, &inside, 5.0, 300.0), None);
+        assert_eq!(avoidance_turn_deg(&ownship, &ownship, 5.0, 300.0), None);
+
+        // Invalid horizons are rejected instead of stepped through
+        assert_eq!(avoidance_turn_deg(&ownship, &intruder, 5.0, f64::INFINITY), None);
+        assert_eq!(avoidance_turn_deg(&ownship, &intruder, 5.0, f64::NAN), None);
+        assert_eq!(avoidance_turn_deg(&ownship, &intruder, 5.0, -300.0), None);
+    }
+
+    #[test]
+    fn test_validate_snapshot() {
+        let mut planes = generate_unique_fleet(30, 17);
+        assert_eq!(validate_snapshot(&planes), Ok(()));
+        assert_eq!(validate_snapshot(&generate_random_airplanes(10, 17)), Ok(()));
+
+        planes[5].callsign = planes[3].callsign.clone();
+        let problems = validate_snapshot(&planes).unwrap_err();
+        assert_eq!(problems, vec![format!("plane 5: duplicate callsign {}", planes[3].callsign)]);
+
+        planes[7].altitude = -10.0;
+        assert_eq!(validate_snapshot(&planes).unwrap_err().len(), 2);
+
+        let problems = validate_snapshot(&planes[1..5]).unwrap_err();
+        assert_eq!(problems, vec!["test plane TEST001 is missing".to_string()]);
+    }
+
+    #[test]
+    fn test_closing_speed() {
+        let (lat, lng) = offset_position(LNZ_LAT, LNZ_LNG, 10.0, 0.0);
+        let southbound = Airplane::new("SB1", "DH8D", lat, lng, 8000.0, 120.0, 180.0);
+        let northbound = Airplane::new("NB1", "DH8D", LNZ_LAT, LNZ_LNG, 8000.0, 120.0, 0.0);
+        assert!((northbound.closing_speed_kn(&southbound) - 240.0).abs() < 1e-9);
+        assert!((southbound.closing_speed_kn(&northbound) - 240.0).abs() < 1e-9);
+        assert!(northbound.closing_speed_kn(&northbound).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_generate_conflict_free() {
+        let planes = generate_conflict_free(150, 23);
+        assert_eq!(planes.len(), 150);
+        assert!(check_all_alerts(&planes).is_empty());
+        assert_eq!(validate_snapshot(&planes), Ok(()));
+
+        // A saturated airspace ends generation early instead of retrying forever
+        let saturated = generate_conflict_free(20000, 1);
+        assert!(saturated.len() > 150 && saturated.len() < 20000);
+        assert!(check_all_alerts(&saturated).is_empty());
+    }
+
+    #[test]
+    fn test_separation_trend() {
+        let planes = generate_demo_airplanes();
+        assert_eq!(separation_trend(&planes[0], &planes[1]), SeparationTrend::Converging);
+        let apart = calculate_airplane_positions(&planes, 600.0);
+        assert_eq!(separation_trend(&apart[0], &apart[1]), SeparationTrend::Diverging);
+
+        let (lat, lng) = offset_position(LNZ_LAT, LNZ_LNG, 0.0, 3.0);
+        let left = test_plane("PAR1", LNZ_LAT, LNZ_LNG, 30000.0, 0.0);
+        let right = test_plane("PAR2", lat, lng, 30000.0, 0.0);
+        assert_eq!(separation_trend(&left, &right), SeparationTrend::Parallel);
+    }
+
+    #[test]
+    fn test_planes_with_tag() {
+        let mut planes = generate_unique_fleet(10, 31);
+        for plane in planes.iter_mut().step_by(3) {
+            plane.tags.push("arrival".to_string());
+        }
+        planes[1].tags.push("vip".to_string());
+
+        let arrivals = planes_with_tag(&planes, "arrival");
+        let callsigns: Vec<&str> = arrivals.iter().map(|p| p.callsign.as_str()).collect();
+        let expected: Vec<&str> =
+            [0, 3, 6, 9].iter().map(|&i| planes[i].callsign.as_str()).collect();
+        assert_eq!(callsigns, expected);
+        assert!(planes_with_tag(&planes, "unknown").is_empty());
+
+        // Tags survive position updates and are serialized
+        let moved = calculate_airplane_positions(&planes, 60.0);
+        assert_eq!(planes_with_tag(&moved, "vip")[0].callsign, planes[1].callsign);
+        let json = serde_json::to_value(&moved[1]).unwrap();
+        assert_eq!(json["tags"], serde_json::json!(["vip"]));
+    }
+
+    #[test]
+    fn test_mean_velocity() {
+        let planes = vec![
+            Airplane::new("E1", "A320", LNZ_LAT, LNZ_LNG, 30000.0, 300.0, 90.0),
+            Airplane::new("E2", "A320", LNZ_LAT + 0.5, LNZ_LNG, 30000.0, 400.0, 80.0),
+            Airplane::new("E3", "A320", LNZ_LAT - 0.5, LNZ_LNG, 30000.0, 400.0, 100.0),
+        ];
+        let (north, east) = mean_velocity_kn(&planes);
+        assert!(north.abs() < 1e-9, "north was {north}");
+        assert!(east > 350.0 && east < 370.0, "east was {east}");
+        assert_eq!(mean_velocity_kn(&[]), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_position_model() {
+        let planes = generate_demo_airplanes();
+        let default_model = PositionModel::default();
+        let expected = calculate_airplane_positions(&planes, 60.0);
+        for (moved, expected) in default_model.positions(&planes, 60.0).iter().zip(&expected) {
+            assert_eq!((moved.latitude, moved.longitude), (expected.latitude, expected.longitude));
+        }
+
+        let northbound = test_plane("NORTH1", LNZ_LAT, LNZ_LNG, 30000.0, 0.0);
+        let doubled = PositionModel { nm_per_degree: 2.0 * math::NM_PER_DEGREE };
+        let earth_offset = default_model.advance(&northbound, 600.0).latitude - LNZ_LAT;
+        let doubled_offset = doubled.advance(&northbound, 600.0).latitude - LNZ_LAT;
+        assert!((doubled_offset - earth_offset / 2.0).abs() < 1e-12);
+    }
 
+    #[test]
+    fn test_level_conflicts() {
+        let (north_lat, north_lng) = offset_position(LNZ_LAT, LNZ_LNG, 20.0, 0.0);
+        let (south_lat, south_lng) = offset_position(LNZ_LAT, LNZ_LNG, -10.0, 0.0);
+        let planes = vec![
+            test_plane("SOUTH", LNZ_LAT, LNZ_LNG, 30000.0, 0.0),
+            test_plane("NORTH", north_lat, north_lng, 30100.0, 180.0),
+            test_plane("ABOVE", north_lat, north_lng, 34000.0, 180.0),
+            // Flies away from SOUTH and keeps its distance to NORTH
+            test_plane("AWAY", south_lat, south_lng, 30000.0, 180.0),
+        ];
+        assert!(check_all_alerts(&planes).is_empty());
+        let expected = vec![("SOUTH".to_string(), "NORTH".to_string())];
+        assert_eq!(level_conflicts(&planes, 200.0), expected);
+        assert!(level_conflicts(&planes, 50.0).is_empty());
+    }
 }
@@ -0,0 +1,273 @@
+//! Pure navigation math (Haversine distance, bearings, position offsets)
+//!
+//! By default the trigonometric functions and `sqrt` are the `std` float methods. With the
+//! `libm` feature enabled they are taken from `libm` instead and the module only depends on
+//! `core`, so it can be reused on `no_std` targets (see `nostd-check/`, which builds it that way).
+//! The two builds agree within a small tolerance but are not guaranteed to be bit-identical.
+
+/// Mean earth radius in nautical miles
+pub const EARTH_RADIUS_NM: f64 = 3440.065;
+/// Nautical miles per degree of latitude (flat-earth approximation)
+pub const NM_PER_DEGREE: f64 = 60.0;
+/// WGS84 equatorial radius in meters (used for local tangent-plane projections)
+pub const EARTH_RADIUS_M: f64 = 6378137.0;
+
+#[cfg(feature = "libm")]
+mod float {
+    pub fn sin(x: f64) -> f64 {
+        libm::sin(x)
+    }
+    pub fn cos(x: f64) -> f64 {
+        libm::cos(x)
+    }
+    pub fn sqrt(x: f64) -> f64 {
+        libm::sqrt(x)
+    }
+    pub fn atan2(y: f64, x: f64) -> f64 {
+        libm::atan2(y, x)
+    }
+}
+
+#[cfg(not(feature = "libm"))]
+mod float {
+    pub fn sin(x: f64) -> f64 {
+        x.sin()
+    }
+    pub fn cos(x: f64) -> f64 {
+        x.cos()
+    }
+    pub fn sqrt(x: f64) -> f64 {
+        x.sqrt()
+    }
+    pub fn atan2(y: f64, x: f64) -> f64 {
+        y.atan2(x)
+    }
+}
+
+/// Normalize an angle in degrees to 0..360
+fn normalize_deg(angle: f64) -> f64 {
+    let normalized = angle % 360.0;
+    if normalized < 0.0 {
+        normalized + 360.0
+    } else {
+        normalized
+    }
+}
+
+/// Great-circle distance between two points in nautical miles (Haversine formula)
+pub fn haversine_distance_nm(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
+    haversine_distance_with_cos_nm(lat1, lng1, cos_deg(lat1), lat2, lng2)
+}
+
+/// Course on arrival at the second point when flying the great circle from the first (0..360°)
+pub fn final_bearing_deg(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
+    (initial_bearing_deg(lat2, lng2, lat1, lng1) + 180.0) % 360.0
+}
+
+/// Cosine of a latitude given in degrees
+pub(crate) fn cos_deg(deg: f64) -> f64 {
+    float::cos(deg.to_radians())
+}
+
+/// [`haversine_distance_nm`] with `cos_lat1` = [`cos_deg`]`(lat1)` precomputed
+///
+/// Lets one-to-many distance computations evaluate the cosine of the first latitude only once.
+pub(crate) fn haversine_distance_with_cos_nm(
+    lat1: f64,
+    lng1: f64,
+    cos_lat1: f64,
+    lat2: f64,
+    lng2: f64,
+) -> f64 {
+    let d_lat = (lat2 - lat1).to_radians();
+    let d_lng = (lng2 - lng1).to_radians();
+    let sin_d_lat = float::sin(d_lat / 2.0);
+    let sin_d_lng = float::sin(d_lng / 2.0);
+    let a = sin_d_lat * sin_d_lat + cos_lat1 * cos_deg(lat2) * sin_d_lng * sin_d_lng;
+    let c = 2.0 * float::atan2(float::sqrt(a), float::sqrt(1.0 - a));
+    EARTH_RADIUS_NM * c
+}
+
+/// Initial great-circle bearing from the first to the second point in degrees (0..360)
+pub fn initial_bearing_deg(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
+    let lat1 = lat1.to_radians();
+    let lat2 = lat2.to_radians();
+    let d_lng = (lng2 - lng1).to_radians();
+    let y = float::sin(d_lng) * float::cos(lat2);
+    let x = float::cos(lat1) * float::sin(lat2)
+        - float::sin(lat1) * float::cos(lat2) * float::cos(d_lng);
+    normalize_deg(float::atan2(y, x).to_degrees())
+}
+
+/// Midpoint of the great circle between two points
+pub fn great_circle_midpoint(start: (f64, f64), end: (f64, f64)) -> (f64, f64) {
+    let (lat1, lng1) = (start.0.to_radians(), start.1.to_radians());
+    let lat2 = end.0.to_radians();
+    let d_lng = (end.1 - start.1).to_radians();
+    let bx = float::cos(lat2) * float::cos(d_lng);
+    let by = float::cos(lat2) * float::sin(d_lng);
+    let lat = float::atan2(
+        float::sin(lat1) + float::sin(lat2),
+        float::sqrt((float::cos(lat1) + bx) * (float::cos(lat1) + bx) + by * by),
+    );
+    let lng = lng1 + float::atan2(by, float::cos(lat1) + bx);
+    (lat.to_degrees(), lng.to_degrees())
+}
+
+/// Point at `fraction` (0..=1) of the way along the great circle from `start` to `end`
+pub fn great_circle_intermediate(start: (f64, f64), end: (f64, f64), fraction: f64) -> (f64, f64) {
+    let delta = haversine_distance_nm(start.0, start.1, end.0, end.1) / EARTH_RADIUS_NM;
+    if delta < 1e-12 {
+        return start;
+    }
+    let (lat1, lng1) = (start.0.to_radians(), start.1.to_radians());
+    let (lat2, lng2) = (end.0.to_radians(), end.1.to_radians());
+    let a = float::sin((1.0 - fraction) * delta) / float::sin(delta);
+    let b = float::sin(fraction * delta) / float::sin(delta);
+    let x = a * float::cos(lat1) * float::cos(lng1) + b * float::cos(lat2) * float::cos(lng2);
+    let y = a * float::cos(lat1) * float::sin(lng1) + b * float::cos(lat2) * float::sin(lng2);
+    let z = a * float::sin(lat1) + b * float::sin(lat2);
+    let lat = float::atan2(z, float::sqrt(x * x + y * y));
+    let lng = float::atan2(y, x);
+    (lat.to_degrees(), lng.to_degrees())
+}
+
+/// Move a position by the given north/east distances (flat-earth approximation)
+pub fn offset_position(lat: f64, lng: f64, north_nm: f64, east_nm: f64) -> (f64, f64) {
+    offset_position_with(lat, lng, north_nm, east_nm, NM_PER_DEGREE)
+}
+
+/// [`offset_position`] with a custom number of nautical miles per degree of latitude
+pub fn offset_position_with(
+    lat: f64,
+    lng: f64,
+    north_nm: f64,
+    east_nm: f64,
+    nm_per_degree: f64,
+) -> (f64, f64) {
+    (
+        lat + north_nm / nm_per_degree,
+        lng + east_nm / (nm_per_degree * float::cos(lat.to_radians())),
+    )
+}
+
+/// Move a position by `distance_nm` along `bearing_deg` (flat-earth approximation)
+pub fn offset_position_along(lat: f64, lng: f64, bearing_deg: f64, distance_nm: f64) -> (f64, f64) {
+    offset_position_along_with(lat, lng, bearing_deg, distance_nm, NM_PER_DEGREE)
+}
+
+/// [`offset_position_along`] with a custom number of nautical miles per degree of latitude
+pub fn offset_position_along_with(
+    lat: f64,
+    lng: f64,
+    bearing_deg: f64,
+    distance_nm: f64,
+    nm_per_degree: f64,
+) -> (f64, f64) {
+    let bearing = bearing_deg.to_radians();
+    offset_position_with(
+        lat,
+        lng,
+        distance_nm * float::cos(bearing),
+        distance_nm * float::sin(bearing),
+        nm_per_degree,
+    )
+}
+
+/// Project a position onto a local east/north tangent plane around the reference (meters)
+///
+/// Uses an equirectangular approximation, which is accurate for the extent of a single
+/// airspace.
+pub fn to_enu_meters(lat: f64, lng: f64, ref_lat: f64, ref_lng: f64) -> (f64, f64) {
+    let east = (lng - ref_lng).to_radians() * float::cos(ref_lat.to_radians()) * EARTH_RADIUS_M;
+    let north = (lat - ref_lat).to_radians() * EARTH_RADIUS_M;
+    (east, north)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Reference implementations using the `std` float methods. `libm` and the platform math
+    // library may round sin, cos and atan2 differently in the last bit, so the builds with and
+    // without the `libm` feature are not bit-identical; they only agree within a tolerance.
+    #[cfg(feature = "libm")]
+    fn std_haversine(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
+        let d_lat = (lat2 - lat1).to_radians();
+        let d_lng = (lng2 - lng1).to_radians();
+        let a = (d_lat / 2.0).sin().powi(2)
+            + lat1.to_radians().cos() * lat2.to_radians().cos() * (d_lng / 2.0).sin().powi(2);
+        EARTH_RADIUS_NM * 2.0 * a.sqrt().atan2((1.0 - a).sqrt())
+    }
+
+    #[cfg(feature = "libm")]
+    fn std_bearing(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
+        let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+        let d_lng = (lng2 - lng1).to_radians();
+        let y = d_lng.sin() * lat2.cos();
+        let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * d_lng.cos();
+        y.atan2(x).to_degrees().rem_euclid(360.0)
+    }
+
+    // Under default features `float::*` is the std implementation, so this only compares
+    // anything when built with `--features libm`
+    #[cfg(feature = "libm")]
+    #[test]
+    fn test_math_agrees_with_std_within_tolerance() {
+        let cases = [
+            (48.25, 14.191473, 48.265, 14.191473),
+            (48.238575, 14.191473, 47.0, 16.5),
+            (-33.9, 151.2, 51.5, -0.1),
+            (10.0, 179.5, 10.0, -179.5),
+        ];
+        for (lat1, lng1, lat2, lng2) in cases {
+            let distance = haversine_distance_nm(lat1, lng1, lat2, lng2);
+            assert!(
+                (distance - std_haversine(lat1, lng1, lat2, lng2)).abs()
+                    <= 1e-9 * distance.max(1.0)
+            );
+            let bearing = initial_bearing_deg(lat1, lng1, lat2, lng2);
+            assert!((bearing - std_bearing(lat1, lng1, lat2, lng2)).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_offset_position() {
+        let (lat, lng) = offset_position(48.0, 14.0, 60.0, 0.0);
+        assert!((lat - 49.0).abs() < 1e-12 && (lng - 14.0).abs() < 1e-12);
+        let (lat, lng) = offset_position_along(0.0, 0.0, 90.0, 60.0);
+        assert!(lat.abs() < 1e-12 && (lng - 1.0).abs() < 1e-12);
+    }
+
+    #[cfg(feature = "libm")]
+    #[test]
+    fn test_libm_sqrt_matches_std_bits() {
+        // Square roots are correctly rounded in both implementations
+        for x in [0.0, 1e-300, 0.5, 2.0, 3.0, 1234.5678, 1e300, f64::MAX] {
+            assert_eq!(float::sqrt(x).to_bits(), x.sqrt().to_bits(), "sqrt({x})");
+        }
+    }
+
+    #[test]
+    fn test_to_enu_meters() {
+        let (east, north) = to_enu_meters(49.238575, 14.191473, 48.238575, 14.191473);
+        assert!((north - 111320.0).abs() < 10.0);
+        assert!(east.abs() < 1e-9);
+
+        let (east, north) = to_enu_meters(0.0, 1.0, 0.0, 0.0);
+        assert!((east - 111320.0).abs() < 10.0);
+        assert!(north.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_final_bearing() {
+        // Eastbound leg along 60°N spanning 50° of longitude, symmetric about its midpoint
+        let initial = initial_bearing_deg(60.0, 10.0, 60.0, 60.0);
+        let final_ = final_bearing_deg(60.0, 10.0, 60.0, 60.0);
+        assert!(initial < 90.0 && final_ > 90.0);
+        assert!((initial + final_ - 180.0).abs() < 1e-9);
+
+        let initial = initial_bearing_deg(40.0, 14.0, 50.0, 14.0);
+        assert!((final_bearing_deg(40.0, 14.0, 50.0, 14.0) - initial).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,70 @@
+//! Async ingestion of traffic snapshots
+
+use std::future::Future;
+use std::time::Duration;
+
+use tokio::time::{Interval, MissedTickBehavior, interval};
+
+use crate::planes::{Airplane, calculate_airplane_positions, generate_demo_airplanes};
+
+/// A source of traffic snapshots, e.g. a live feed or a simulation
+pub trait TrafficSource {
+    /// Wait for the next snapshot; `None` means the source is exhausted
+    fn next_snapshot(&mut self) -> impl Future<Output = Option<Vec<Airplane>>> + Send;
+}
+
+/// Simulated traffic source yielding updated demo positions at a fixed interval
+pub struct DemoSource {
+    planes: Vec<Airplane>,
+    interval: Interval,
+    step: Duration,
+    elapsed_s: f64,
+}
+
+impl DemoSource {
+    /// Simulate the generated demo airplanes
+    pub fn new(step: Duration) -> Self {
+        Self::with_planes(generate_demo_airplanes(), step)
+    }
+
+    /// Simulate the given initial planes
+    pub fn with_planes(planes: Vec<Airplane>, step: Duration) -> Self {
+        let mut interval = interval(step);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        DemoSource { planes, interval, step, elapsed_s: 0.0 }
+    }
+}
+
+impl TrafficSource for DemoSource {
+    async fn next_snapshot(&mut self) -> Option<Vec<Airplane>> {
+        self.interval.tick().await;
+        let snapshot = calculate_airplane_positions(&self.planes, self.elapsed_s);
+        self.elapsed_s += self.step.as_secs_f64();
+        Some(snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_demo_source_snapshots() {
+        let planes = vec![Airplane::new("N1", "A320", 48.0, 14.0, 30000.0, 3600.0, 0.0)];
+        let mut source = DemoSource::with_planes(planes, Duration::from_millis(10));
+
+        let mut latitudes = Vec::new();
+        let mut stamps = Vec::new();
+        for _ in 0..3 {
+            let snapshot = source.next_snapshot().await.unwrap();
+            assert_eq!(snapshot.len(), 1);
+            latitudes.push(snapshot[0].latitude);
+            stamps.push(snapshot[0].last_update_s);
+        }
+
+        // 3600kn moves the plane 0.01nm per 10ms step
+        assert_eq!(latitudes[0], 48.0);
+        assert!((latitudes[2] - (48.0 + 0.02 / 60.0)).abs() < 1e-9);
+        assert_eq!(stamps, [0.0, 0.01, 0.02]);
+    }
+}
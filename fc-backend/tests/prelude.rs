@@ -0,0 +1,11 @@
+use fc_backend::prelude::*;
+
+#[test]
+fn test_prelude_imports() {
+    let planes: Vec<Airplane> = generate_demo_airplanes();
+    assert!(!planes.is_empty());
+
+    let moved = calculate_airplane_positions(&planes, 60.0);
+    let alerts: Vec<Alert> = check_all_alerts(&moved);
+    assert!(!alerts.is_empty());
+}